@@ -0,0 +1,186 @@
+//! [`Arbitrary`] implementations for fuzzing the serialized definitions.
+//!
+//! The generated values are structurally valid: commands only act on the
+//! registers declared by their circuit, and parameters are small numeric or
+//! symbolic expressions. They are not guaranteed to be semantically valid
+//! pytket circuits (e.g. operation arities are not checked).
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::circuit_json::{Command, ImplicitPermutation, Operation, SerialCircuit};
+use crate::optype::OpType;
+use crate::pass::standard::StandardPass;
+use crate::pass::{BasePass, Predicate, RepeatPass, RepeatUntilSatisfiedPass, SequencePass};
+use crate::register::{Bit, ElementId, Qubit};
+
+/// Maximum number of qubits or bits in a generated circuit.
+const MAX_REGISTER_SIZE: usize = 8;
+
+/// Maximum nesting depth of generated compound passes.
+const MAX_PASS_DEPTH: usize = 3;
+
+/// Symbols used in generated parameter expressions.
+const SYMBOLS: &[&str] = &["a", "b", "theta"];
+
+/// Standard passes without any configuration.
+const UNIT_PASSES: &[StandardPass] = &[
+    StandardPass::CommuteThroughMultis,
+    StandardPass::DecomposeArbitrarilyControlledGates,
+    StandardPass::DecomposeMultiQubitsCX,
+    StandardPass::DecomposeSingleQubitsTK1,
+    StandardPass::RebaseTket,
+    StandardPass::RemoveRedundancies,
+    StandardPass::SynthesiseTket,
+    StandardPass::SquashTK1,
+    StandardPass::FlattenRegisters,
+    StandardPass::RemoveBarriers,
+    StandardPass::RemovePhaseOps,
+    StandardPass::NormaliseTK2,
+];
+
+/// Predicates without any configuration.
+const UNIT_PREDICATES: &[Predicate] = &[
+    Predicate::NoClassicalControlPredicate,
+    Predicate::NoWireSwapsPredicate,
+    Predicate::MaxTwoQubitGatesPredicate,
+    Predicate::CliffordCircuitPredicate,
+    Predicate::NoBarriersPredicate,
+    Predicate::NoSymbolsPredicate,
+];
+
+impl<'a> Arbitrary<'a> for OpType {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(OpType::all()).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for ElementId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let register = u.choose(&["q", "c", "node"])?;
+        let index = u.int_in_range(0..=MAX_REGISTER_SIZE as i64)?;
+        Ok(ElementId(register.to_string(), vec![index]))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Operation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut op = Operation::from_optype(u.arbitrary()?);
+        if u.arbitrary()? {
+            let n_params = u.int_in_range(0..=3)?;
+            op.params = Some(
+                (0..n_params)
+                    .map(|_| arbitrary_param(u))
+                    .collect::<Result<_>>()?,
+            );
+        }
+        Ok(op)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Command {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Command {
+            op: u.arbitrary()?,
+            args: u
+                .arbitrary_iter()?
+                .take(MAX_REGISTER_SIZE)
+                .collect::<Result<_>>()?,
+            opgroup: None,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for SerialCircuit {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let n_qubits = u.int_in_range(0..=MAX_REGISTER_SIZE)?;
+        let n_bits = u.int_in_range(0..=MAX_REGISTER_SIZE)?;
+        let mut circuit = SerialCircuit::new(None, arbitrary_param(u)?);
+        circuit.qubits = (0..n_qubits)
+            .map(|i| Qubit::from(ElementId("q".to_string(), vec![i as i64])))
+            .collect();
+        circuit.bits = (0..n_bits)
+            .map(|i| Bit::from(ElementId("c".to_string(), vec![i as i64])))
+            .collect();
+
+        let units: Vec<ElementId> = circuit
+            .qubits
+            .iter()
+            .map(|q| q.id.clone())
+            .chain(circuit.bits.iter().map(|b| b.id.clone()))
+            .collect();
+        if !units.is_empty() {
+            for _ in 0..u.int_in_range(0..=16)? {
+                let op = u.arbitrary()?;
+                let args = (0..u.int_in_range(1..=3)?)
+                    .map(|_| u.choose(&units).cloned())
+                    .collect::<Result<_>>()?;
+                circuit.commands.push(Command {
+                    op,
+                    args,
+                    opgroup: None,
+                });
+            }
+        }
+
+        let mut targets = circuit.qubits.clone();
+        for i in (1..targets.len()).rev() {
+            targets.swap(i, u.int_in_range(0..=i)?);
+        }
+        circuit.implicit_permutation = circuit
+            .qubits
+            .iter()
+            .cloned()
+            .zip(targets)
+            .map(|(source, target)| ImplicitPermutation(source, target))
+            .collect();
+        Ok(circuit)
+    }
+}
+
+impl<'a> Arbitrary<'a> for BasePass {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_pass(u, MAX_PASS_DEPTH)
+    }
+}
+
+/// Generates a pass, nesting compound passes at most `depth` levels deep.
+fn arbitrary_pass(u: &mut Unstructured<'_>, depth: usize) -> Result<BasePass> {
+    let choice = if depth == 0 {
+        0
+    } else {
+        u.int_in_range(0..=3)?
+    };
+    let pass = match choice {
+        0 => BasePass::StandardPass {
+            pass: u.choose(UNIT_PASSES)?.clone(),
+        },
+        1 => BasePass::SequencePass {
+            pass: SequencePass {
+                sequence: (0..u.int_in_range(0..=3)?)
+                    .map(|_| arbitrary_pass(u, depth - 1))
+                    .collect::<Result<_>>()?,
+            },
+        },
+        2 => BasePass::RepeatPass {
+            pass: RepeatPass {
+                body: Box::new(arbitrary_pass(u, depth - 1)?),
+            },
+        },
+        _ => BasePass::RepeatUntilSatisfiedPass {
+            pass: RepeatUntilSatisfiedPass {
+                body: Box::new(arbitrary_pass(u, depth - 1)?),
+                predicate: u.choose(UNIT_PREDICATES)?.clone(),
+            },
+        },
+    };
+    Ok(pass)
+}
+
+/// Generates a parameter expression, either a multiple of 1/8 or a symbol.
+fn arbitrary_param(u: &mut Unstructured<'_>) -> Result<String> {
+    if u.ratio(1, 4)? {
+        Ok(u.choose(SYMBOLS)?.to_string())
+    } else {
+        Ok((u.int_in_range(-16..=16)? as f64 / 8.0).to_string())
+    }
+}
@@ -0,0 +1,34 @@
+//! CBOR encoding for serialized circuits.
+//!
+//! The encoding uses the same serde model as the JSON format, so a circuit
+//! can be converted between both formats without loss.
+
+use derive_more::{Display, Error, From};
+
+use crate::circuit_json::SerialCircuit;
+
+/// Error returned when encoding or decoding a circuit as CBOR.
+#[derive(Debug, Display, Error, From)]
+#[non_exhaustive]
+pub enum CborError {
+    /// The circuit could not be encoded.
+    #[display("failed to encode circuit as CBOR: {_0}")]
+    Encode(ciborium::ser::Error<std::io::Error>),
+    /// The input is not a valid CBOR encoded circuit.
+    #[display("failed to decode circuit from CBOR: {_0}")]
+    Decode(ciborium::de::Error<std::io::Error>),
+}
+
+impl SerialCircuit {
+    /// Encodes the circuit as CBOR.
+    pub fn to_cbor(&self) -> Result<Vec<u8>, CborError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decodes a circuit from its CBOR encoding.
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, CborError> {
+        Ok(ciborium::from_reader(bytes)?)
+    }
+}
@@ -1,13 +1,34 @@
 //! Contains structs for serializing and deserializing TKET circuits to and from
 //! JSON.
+//!
+//! Names used by older pytket 1.x releases, such as the `Composite` custom
+//! gates or `Condition` operations, are accepted when deserializing. The
+//! current pytket 2.x spelling is always used when serializing.
+
+mod analysis;
+mod builder;
+mod dot;
+#[cfg(feature = "clifford")]
+pub mod pauli_frame;
+mod quantikz;
+pub mod stim;
+pub mod tensor_network;
+pub mod transform;
+pub mod validate;
+
+pub use builder::CircuitBuilder;
+
+use std::collections::HashMap;
 
 use crate::clexpr::ClExpr;
 use crate::opbox::OpBox;
 use crate::optype::OpType;
+use crate::param;
 use crate::register::{Bit, BitRegister, ElementId, Qubit};
 
 #[cfg(feature = "schemars")]
 use schemars::JsonSchema;
+use serde::de::{self, DeserializeOwned};
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// A gate defined by a circuit.
@@ -144,6 +165,7 @@ pub struct Wasm {
 /// Serializable operation descriptor.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(bound(deserialize = "P: Deserialize<'de>"))]
 #[non_exhaustive]
 pub struct Operation<P = String> {
     /// The type of operation.
@@ -156,6 +178,9 @@ pub struct Operation<P = String> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<String>,
     /// Expressions for the parameters of the operation.
+    ///
+    /// Parameters given as bare JSON numbers are accepted when deserializing.
+    #[serde(default, deserialize_with = "param::deserialize_params")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub params: Option<Vec<P>>,
     /// Internal box for the operation.
@@ -183,21 +208,54 @@ pub struct Operation<P = String> {
 }
 
 /// Operation applied in a circuit, with defined arguments.
+///
+/// Circuits are serialized as a flat list of commands in application order,
+/// with the units each command acts on listed in its `args`. There is no
+/// form describing the circuit as a DAG, and commands carrying explicit
+/// `wires` are rejected with an error rather than having that structure
+/// silently dropped.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq)]
 pub struct Command<P = String> {
     /// The operation to be applied.
     pub op: Operation<P>,
     /// The arguments to the operation.
     ///
     /// May correspond to either [`Qubit`]s or [`Bit`]s, depending on the operation.
-    #[serde(deserialize_with = "deserialize_command_args")]
     pub args: Vec<ElementId>,
     /// Operation group identifier.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub opgroup: Option<String>,
 }
 
+/// The serialized fields of a [`Command`].
+#[derive(Deserialize)]
+struct CommandFields<P> {
+    op: Operation<P>,
+    #[serde(deserialize_with = "deserialize_command_args")]
+    args: Vec<ElementId>,
+    opgroup: Option<String>,
+    /// Wire indices of a DAG form, which is not supported.
+    wires: Option<de::IgnoredAny>,
+}
+
+impl<'de, P: Deserialize<'de>> Deserialize<'de> for Command<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = CommandFields::deserialize(deserializer)?;
+        if fields.wires.is_some() {
+            return Err(de::Error::custom(
+                "commands with explicit wires are not supported, \
+                 list the units each command acts on in its args",
+            ));
+        }
+        Ok(Command {
+            op: fields.op,
+            args: fields.args,
+            opgroup: fields.opgroup,
+        })
+    }
+}
+
 /// Deserialize the arguments of a command.
 ///
 /// pytket sometimes emits a "null" value for commands with no arguments, so we
@@ -230,6 +288,7 @@ pub struct SerialCircuit<P = String> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     /// The global phase, as a symengine expression.
+    #[serde(deserialize_with = "param::deserialize_param")]
     pub phase: P,
     /// List of commands in the circuit.
     pub commands: Vec<Command<P>>,
@@ -238,6 +297,11 @@ pub struct SerialCircuit<P = String> {
     /// Input bits.
     pub bits: Vec<Bit>,
     /// Implicit permutation of the output qubits.
+    ///
+    /// Qubits that are not listed are mapped to themselves, so an empty list
+    /// is the identity. A missing field is read as the identity, and the
+    /// field is always written, as pytket does.
+    #[serde(default)]
     pub implicit_permutation: Vec<ImplicitPermutation>,
     /// Number of wasm wires in the circuit.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -251,6 +315,26 @@ pub struct SerialCircuit<P = String> {
     /// A list of qubits discarded at the end of the circuit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub discarded_qubits: Option<Vec<Bit>>,
+    /// The schema version the circuit was serialized with, if recorded.
+    ///
+    /// pytket does not currently emit this field, but other producers may use
+    /// it to tag their output. See [`SerialCircuit::check_schema_version`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<String>,
+}
+
+/// Result of checking the schema version recorded in a [`SerialCircuit`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SchemaVersionCheck {
+    /// The circuit does not record a schema version.
+    Unversioned,
+    /// The circuit was serialized with the schema version targeted by this crate.
+    Supported,
+    /// The circuit was serialized with an unexpected schema version.
+    ///
+    /// The circuit may still parse, but some fields may be misinterpreted.
+    Unsupported(String),
 }
 
 impl<P> Default for Operation<P> {
@@ -283,6 +367,7 @@ impl<P: Default> Default for SerialCircuit<P> {
             number_of_rs: None,
             created_qubits: None,
             discarded_qubits: None,
+            schema_version: None,
         }
     }
 }
@@ -299,6 +384,24 @@ impl<P> Operation<P> {
         }
     }
 
+    /// Returns the parameters of the operation.
+    ///
+    /// Operations without parameters return an empty slice.
+    pub fn params(&self) -> &[P] {
+        self.params.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the constants written by a `SetBits` operation.
+    ///
+    /// The value at each position is written to the bit at the same position
+    /// in the command's arguments. Returns `None` for any other operation.
+    pub fn set_bits_values(&self) -> Option<&[bool]> {
+        match self.classical.as_deref() {
+            Some(Classical::SetBits { values }) if self.op_type == OpType::SetBits => Some(values),
+            _ => None,
+        }
+    }
+
     /// Applies a function over the parameters of the operation.
     ///
     /// Returns a new Operation with the same data, but with a new generic
@@ -349,6 +452,7 @@ impl<P> SerialCircuit<P> {
             number_of_rs: None,
             created_qubits: None,
             discarded_qubits: None,
+            schema_version: None,
         }
     }
 
@@ -374,6 +478,171 @@ impl<P> SerialCircuit<P> {
             number_of_rs: self.number_of_rs,
             created_qubits: self.created_qubits,
             discarded_qubits: self.discarded_qubits,
+            schema_version: self.schema_version,
+        }
+    }
+
+    /// Returns the schema version recorded in the circuit, if any.
+    pub fn schema_version(&self) -> Option<&str> {
+        self.schema_version.as_deref()
+    }
+
+    /// Returns the bits of the circuit in the order their values appear in a
+    /// shot.
+    ///
+    /// As in pytket's `bit_readout`, which is computed rather than
+    /// serialized, the bits are read out in declaration order.
+    pub fn readout_order(&self) -> Vec<&ElementId> {
+        self.bits.iter().map(|bit| &bit.id).collect()
+    }
+
+    /// Returns the position in the readout of a shot of each measured qubit,
+    /// as in pytket's `qubit_readout`.
+    ///
+    /// A qubit is read out at the position of the bit its last top-level
+    /// [`OpType::Measure`] writes to, see [`SerialCircuit::readout_order`].
+    /// Measurements into undeclared bits are ignored.
+    pub fn qubit_readout(&self) -> HashMap<&ElementId, usize> {
+        let positions: HashMap<&ElementId, usize> = self
+            .readout_order()
+            .into_iter()
+            .enumerate()
+            .map(|(index, bit)| (bit, index))
+            .collect();
+        let mut readout = HashMap::new();
+        for command in &self.commands {
+            if let (OpType::Measure, [qubit, bit]) = (command.op.op_type, &command.args[..]) {
+                if let Some(&index) = positions.get(bit) {
+                    readout.insert(qubit, index);
+                }
+            }
+        }
+        readout
+    }
+
+    /// Checks the recorded schema version against the one targeted by this
+    /// crate, [`SUPPORTED_CIRCUIT_SCHEMA`](crate::SUPPORTED_CIRCUIT_SCHEMA).
+    pub fn check_schema_version(&self) -> SchemaVersionCheck {
+        match self.schema_version() {
+            None => SchemaVersionCheck::Unversioned,
+            Some(crate::SUPPORTED_CIRCUIT_SCHEMA) => SchemaVersionCheck::Supported,
+            Some(version) => SchemaVersionCheck::Unsupported(version.to_string()),
+        }
+    }
+}
+
+impl<P: Clone> SerialCircuit<P> {
+    /// Returns a copy of the circuit's metadata and declarations, with the
+    /// given list of commands.
+    pub(crate) fn clone_with_commands(&self, commands: Vec<Command<P>>) -> Self {
+        Self {
+            name: self.name.clone(),
+            phase: self.phase.clone(),
+            commands,
+            qubits: self.qubits.clone(),
+            bits: self.bits.clone(),
+            implicit_permutation: self.implicit_permutation.clone(),
+            number_of_ws: self.number_of_ws,
+            number_of_rs: self.number_of_rs,
+            created_qubits: self.created_qubits.clone(),
+            discarded_qubits: self.discarded_qubits.clone(),
+            schema_version: self.schema_version.clone(),
+        }
+    }
+}
+
+impl<P: DeserializeOwned> SerialCircuit<P> {
+    /// Parses a circuit from a JSON string, regardless of its schema version.
+    ///
+    /// Returns the parsed circuit along with the result of
+    /// [`SerialCircuit::check_schema_version`], so callers can warn about
+    /// circuits serialized with an unexpected schema.
+    pub fn from_json_lenient(json: &str) -> serde_json::Result<(Self, SchemaVersionCheck)> {
+        let circuit: Self = serde_json::from_str(json)?;
+        let check = circuit.check_schema_version();
+        Ok((circuit, check))
+    }
+}
+
+impl<P: Serialize> SerialCircuit<P> {
+    /// Writes the circuit as compact JSON to `writer`.
+    ///
+    /// Unlike [`serde_json::to_string`], this streams the output without
+    /// building it in memory first. Wrap unbuffered writers such as files in
+    /// a [`std::io::BufWriter`].
+    pub fn write_json<W: std::io::Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Writes the circuit as indented JSON to `writer`.
+    ///
+    /// See [`SerialCircuit::write_json`].
+    pub fn write_json_pretty<W: std::io::Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+}
+
+impl Operation {
+    /// Applies a function to every parameter of the operation, in place.
+    ///
+    /// Unlike [`Operation::map_params`], this also updates the parameters of
+    /// any nested operation, including those in conditionals, multi-bit
+    /// operations, and boxes. The parameters held by boxes themselves (see
+    /// [`OpBox::params`]) and the global phase of circuits nested in boxes
+    /// are updated too.
+    pub fn map_params_in_place(&mut self, mut f: impl FnMut(&str) -> String) {
+        self.map_params_in_place_dyn(&mut f);
+    }
+
+    fn map_params_in_place_dyn(&mut self, f: &mut dyn FnMut(&str) -> String) {
+        for param in self.params.iter_mut().flatten() {
+            *param = f(param);
+        }
+        if let Some(conditional) = &mut self.conditional {
+            conditional.op.map_params_in_place_dyn(f);
+        }
+        if let Some(classical) = &mut self.classical {
+            if let Classical::MultiBit { op, .. } = classical.as_mut() {
+                op.map_params_in_place_dyn(f);
+            }
+        }
+        if let Some(op_box) = &mut self.op_box {
+            for param in op_box.params_mut() {
+                *param = f(param);
+            }
+            for circuit in op_box.circuits_mut() {
+                circuit.map_params_in_place_dyn(f);
+            }
+            for op in op_box.operations_mut() {
+                op.map_params_in_place_dyn(f);
+            }
+        }
+    }
+}
+
+impl Command {
+    /// Applies a function to every parameter of the command, in place.
+    ///
+    /// See [`Operation::map_params_in_place`].
+    pub fn map_params_in_place(&mut self, f: impl FnMut(&str) -> String) {
+        self.op.map_params_in_place(f);
+    }
+}
+
+impl SerialCircuit {
+    /// Applies a function to the global phase and every gate parameter of
+    /// the circuit, in place.
+    ///
+    /// Unlike [`SerialCircuit::map_params`], this also updates parameters
+    /// nested inside boxes. See [`Operation::map_params_in_place`].
+    pub fn map_params_in_place(&mut self, mut f: impl FnMut(&str) -> String) {
+        self.map_params_in_place_dyn(&mut f);
+    }
+
+    fn map_params_in_place_dyn(&mut self, f: &mut dyn FnMut(&str) -> String) {
+        self.phase = f(&self.phase);
+        for command in &mut self.commands {
+            command.op.map_params_in_place_dyn(f);
         }
     }
 }
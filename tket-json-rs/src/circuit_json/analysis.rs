@@ -0,0 +1,580 @@
+//! Read-only queries over serialized circuits.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use super::{Classical, Command, Operation, SerialCircuit};
+use crate::opbox::OpBox;
+use crate::optype::OpType;
+use crate::param;
+use crate::register::ElementId;
+
+/// Operation types that read or write classical bits.
+///
+/// This includes the classical control flow and RNG operations, and the
+/// assertion boxes, which write their outcome to a bit.
+const CLASSICAL_OP_TYPES: &[OpType] = &[
+    OpType::ClInput,
+    OpType::ClOutput,
+    OpType::Branch,
+    OpType::Goto,
+    OpType::Stop,
+    OpType::Measure,
+    OpType::Conditional,
+    OpType::ClassicalTransform,
+    OpType::WASM,
+    OpType::SetBits,
+    OpType::CopyBits,
+    OpType::RangePredicate,
+    OpType::ExplicitPredicate,
+    OpType::ExplicitModifier,
+    OpType::MultiBit,
+    OpType::StabiliserAssertionBox,
+    OpType::ProjectorAssertionBox,
+    OpType::ClassicalExpBox,
+    OpType::ClExpr,
+    OpType::RNGInput,
+    OpType::RNGOutput,
+    OpType::RNGSeed,
+    OpType::RNGBound,
+    OpType::RNGIndex,
+    OpType::RNGNum,
+    OpType::JobShotNum,
+];
+
+impl<P> SerialCircuit<P> {
+    /// Returns the set of operation types used by the circuit's commands.
+    ///
+    /// Operations nested inside boxes or conditionals are not inspected, see
+    /// [`SerialCircuit::gate_set_recursive`].
+    pub fn gate_set(&self) -> BTreeSet<OpType> {
+        self.commands.iter().map(|c| c.op.op_type).collect()
+    }
+
+    /// Returns the set of operation types used by the circuit, including the
+    /// operations nested inside boxes, conditionals, and multi-bit operations.
+    ///
+    /// The types of the wrapping operations (e.g. [`OpType::CircBox`] or
+    /// [`OpType::Conditional`]) are included as well.
+    pub fn gate_set_recursive(&self) -> BTreeSet<OpType> {
+        let mut gates = BTreeSet::new();
+        for command in &self.commands {
+            command.op.collect_op_types(&mut gates);
+        }
+        gates
+    }
+
+    /// Returns the `(qubit, bit)` pairs of every measurement in the circuit,
+    /// in command order.
+    ///
+    /// Only top-level [`OpType::Measure`] commands are considered.
+    pub fn measurement_map(&self) -> Vec<(ElementId, ElementId)> {
+        self.commands
+            .iter()
+            .filter(|c| c.op.op_type == OpType::Measure)
+            .filter_map(|c| match &c.args[..] {
+                [qubit, bit] => Some((qubit.clone(), bit.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the circuit contains no measurements, conditional
+    /// operations, or classical operations, including inside boxes.
+    ///
+    /// Classical bits may still be declared, as long as no operation uses
+    /// them.
+    pub fn is_purely_quantum(&self) -> bool {
+        let gates = self.gate_set_recursive();
+        !CLASSICAL_OP_TYPES.iter().any(|op| gates.contains(op))
+    }
+
+    /// Returns `true` if any operation in the circuit, including inside
+    /// boxes, is conditioned on classical bits.
+    pub fn has_classical_control(&self) -> bool {
+        self.gate_set_recursive().contains(&OpType::Conditional)
+    }
+
+    /// Returns the bits used as conditions by the conditional commands of the
+    /// circuit.
+    ///
+    /// The condition bits of a [`OpType::Conditional`] command are its first
+    /// `width` arguments. Nested conditionals contribute the arguments
+    /// following those of their parent. Only top-level commands are
+    /// considered.
+    pub fn condition_bits(&self) -> BTreeSet<ElementId> {
+        let mut bits = BTreeSet::new();
+        for command in &self.commands {
+            let mut args = &command.args[..];
+            let mut conditional = command.op.conditional.as_ref();
+            while let Some(cond) = conditional {
+                let width = (cond.width as usize).min(args.len());
+                bits.extend(args[..width].iter().cloned());
+                args = &args[width..];
+                conditional = cond.op.conditional.as_ref();
+            }
+        }
+        bits
+    }
+
+    /// Returns the indices of the top-level [`OpType::Barrier`] commands,
+    /// along with the `data` string of each barrier, if any.
+    ///
+    /// The data of a barrier is a free-form label used by some compilation
+    /// passes and backends to give it custom semantics.
+    pub fn barriers(&self) -> Vec<(usize, Option<&str>)> {
+        self.commands
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.op.op_type == OpType::Barrier)
+            .map(|(index, c)| (index, c.op.data.as_deref()))
+            .collect()
+    }
+
+    /// Returns the [`opgroup`](super::Command::opgroup) labels used by the
+    /// top-level commands.
+    ///
+    /// Passes such as `DecomposeBoxes` can include or exclude the commands of
+    /// specific opgroups.
+    pub fn opgroups(&self) -> BTreeSet<&str> {
+        self.commands
+            .iter()
+            .filter_map(|c| c.opgroup.as_deref())
+            .collect()
+    }
+
+    /// Returns the indices of the top-level commands in the opgroup `name`.
+    pub fn commands_in_opgroup(&self, name: &str) -> Vec<usize> {
+        self.commands
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.opgroup.as_deref() == Some(name))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Returns the number of top-level measurements in the circuit.
+    pub fn n_measurements(&self) -> usize {
+        self.commands
+            .iter()
+            .filter(|c| c.op.op_type == OpType::Measure)
+            .count()
+    }
+
+    /// Returns the indices of the top-level measurements that are not
+    /// followed by any other command on their qubit.
+    ///
+    /// Barriers do not count as subsequent commands. Measurements nested in
+    /// boxes or conditional operations are not considered.
+    pub fn terminal_measurements(&self) -> Vec<usize> {
+        let mut used_later: HashSet<&ElementId> = HashSet::new();
+        let mut terminal = Vec::new();
+        for (index, command) in self.commands.iter().enumerate().rev() {
+            let op_type = command.op.op_type;
+            if op_type == OpType::Barrier {
+                continue;
+            }
+            if op_type == OpType::Measure
+                && command
+                    .args
+                    .first()
+                    .is_some_and(|q| !used_later.contains(q))
+            {
+                terminal.push(index);
+            }
+            used_later.extend(command.args.iter());
+        }
+        terminal.reverse();
+        terminal
+    }
+
+    /// Partitions the commands into layers of increasing depth.
+    ///
+    /// Each layer lists the indices into
+    /// [`commands`](SerialCircuit#structfield.commands) of the commands at that
+    /// depth, in order. A command is placed in the layer after the last
+    /// command sharing any qubit or bit with it, so the commands within a
+    /// layer act on disjoint arguments.
+    pub fn layers(&self) -> Vec<Vec<usize>> {
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+        let mut unit_depth: HashMap<&ElementId, usize> = HashMap::new();
+        for (index, command) in self.commands.iter().enumerate() {
+            let depth = command
+                .args
+                .iter()
+                .filter_map(|arg| unit_depth.get(arg))
+                .max()
+                .map_or(0, |d| d + 1);
+            for arg in &command.args {
+                unit_depth.insert(arg, depth);
+            }
+            if depth == layers.len() {
+                layers.push(Vec::new());
+            }
+            layers[depth].push(index);
+        }
+        layers
+    }
+
+    /// Returns the indices of the first and last commands acting on each
+    /// qubit.
+    ///
+    /// Qubits that are not used by any command are omitted. Only the
+    /// declared qubits of the circuit are considered, so bit arguments are
+    /// ignored.
+    pub fn qubit_lifetimes(&self) -> HashMap<ElementId, (usize, usize)> {
+        let declared = self.qubit_ids();
+        let mut lifetimes: HashMap<ElementId, (usize, usize)> = HashMap::new();
+        for (index, command) in self.commands.iter().enumerate() {
+            for arg in command.args.iter().filter(|arg| declared.contains(arg)) {
+                lifetimes
+                    .entry(arg.clone())
+                    .and_modify(|(_, last)| *last = index)
+                    .or_insert((index, index));
+            }
+        }
+        lifetimes
+    }
+
+    /// Returns the number of top-level commands acting on each qubit.
+    ///
+    /// Every declared qubit is listed, with a count of zero if it is idle.
+    /// Barriers are not counted, and bit arguments are ignored.
+    pub fn qubit_depths(&self) -> HashMap<ElementId, usize> {
+        let mut depths: HashMap<ElementId, usize> =
+            self.qubits.iter().map(|q| (q.id.clone(), 0)).collect();
+        for command in &self.commands {
+            if command.op.op_type == OpType::Barrier {
+                continue;
+            }
+            for arg in &command.args {
+                if let Some(depth) = depths.get_mut(arg) {
+                    *depth += 1;
+                }
+            }
+        }
+        depths
+    }
+
+    /// Returns the number of control qubits of the command at `cmd_index`.
+    ///
+    /// Fixed-arity controlled gates use [`OpType::n_controls`], and
+    /// [`OpBox::QControlBox`]es the number recorded in the box. The variadic
+    /// gates such as [`OpType::CnX`] or [`OpType::CnRy`] control a single
+    /// target, so their count is inferred as the number of arguments minus
+    /// one. Returns `None` for other operations, or if there is no command
+    /// at the index.
+    pub fn control_count_of(&self, cmd_index: usize) -> Option<u32> {
+        let command = self.commands.get(cmd_index)?;
+        let op_type = command.op.op_type;
+        if let Some(n_controls) = op_type.n_controls() {
+            return Some(n_controls);
+        }
+        match (op_type, &command.op.op_box) {
+            (OpType::QControlBox, Some(OpBox::QControlBox { n_controls, .. })) => Some(*n_controls),
+            (
+                OpType::CnX
+                | OpType::CnY
+                | OpType::CnZ
+                | OpType::CnRx
+                | OpType::CnRy
+                | OpType::CnRz,
+                _,
+            ) => u32::try_from(command.args.len().checked_sub(1)?).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the two-qubit interaction graph of the circuit.
+    ///
+    /// Each entry is a pair of qubits acted on together by at least one
+    /// two-qubit command, along with the number of such commands. Pairs are
+    /// unordered, so `CX(a, b)` and `CX(b, a)` count towards the same edge,
+    /// and the qubits of each pair are listed in ascending order. Bit
+    /// arguments are ignored, as are commands acting on other numbers of qubits.
+    pub fn interaction_graph(&self) -> Vec<(ElementId, ElementId, usize)> {
+        let declared = self.qubit_ids();
+        let mut edges: BTreeMap<(&ElementId, &ElementId), usize> = BTreeMap::new();
+        for command in &self.commands {
+            let qubits: Vec<&ElementId> = command
+                .args
+                .iter()
+                .filter(|arg| declared.contains(arg))
+                .collect();
+            if let [a, b] = qubits[..] {
+                if a != b {
+                    *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+                }
+            }
+        }
+        edges
+            .into_iter()
+            .map(|((a, b), count)| (a.clone(), b.clone(), count))
+            .collect()
+    }
+}
+
+impl<P> SerialCircuit<P> {
+    /// Returns the qubits initialised in the zero state by the circuit.
+    ///
+    /// This includes the qubits listed in the
+    /// [`created_qubits`](SerialCircuit#structfield.created_qubits) field as
+    /// well as the arguments of any [`OpType::Create`] command, without
+    /// duplicates and in order of appearance.
+    pub fn created_qubits(&self) -> Vec<&ElementId> {
+        let declared = self.created_qubits.iter().flatten().map(|q| &q.id);
+        self.lifecycle_qubits(declared, OpType::Create)
+    }
+
+    /// Returns the qubits discarded at the end of the circuit.
+    ///
+    /// This includes the qubits listed in the
+    /// [`discarded_qubits`](SerialCircuit#structfield.discarded_qubits) field
+    /// as well as the arguments of any [`OpType::Discard`] command, without
+    /// duplicates and in order of appearance.
+    pub fn discarded_qubits(&self) -> Vec<&ElementId> {
+        let declared = self.discarded_qubits.iter().flatten().map(|q| &q.id);
+        self.lifecycle_qubits(declared, OpType::Discard)
+    }
+
+    /// Chains the `declared` qubits with the arguments of `op_type` commands,
+    /// removing duplicates.
+    fn lifecycle_qubits<'a>(
+        &'a self,
+        declared: impl Iterator<Item = &'a ElementId>,
+        op_type: OpType,
+    ) -> Vec<&'a ElementId> {
+        let from_commands = self
+            .commands
+            .iter()
+            .filter(|c| c.op.op_type == op_type)
+            .flat_map(|c| &c.args);
+        let mut seen = HashSet::new();
+        declared
+            .chain(from_commands)
+            .filter(|&id| seen.insert(id))
+            .collect()
+    }
+}
+
+impl<P> SerialCircuit<P> {
+    /// Returns `true` if `id` is one of the qubits declared in the circuit.
+    ///
+    /// Qubits and bits are declared in separate lists, so an identifier can
+    /// be checked against the right kind of unit before treating it as one.
+    pub fn is_qubit(&self, id: &ElementId) -> bool {
+        self.qubits.iter().any(|q| &q.id == id)
+    }
+
+    /// Returns `true` if `id` is one of the bits declared in the circuit.
+    ///
+    /// See [`SerialCircuit::is_qubit`].
+    pub fn is_bit(&self, id: &ElementId) -> bool {
+        self.bits.iter().any(|b| &b.id == id)
+    }
+}
+
+impl<P: PartialEq> Command<P> {
+    /// Returns `true` if the commands are equal, ignoring their
+    /// [`opgroup`](Command::opgroup) labels.
+    ///
+    /// Only the label of the command itself is ignored. The commands of
+    /// circuits nested in boxes are compared exactly.
+    pub fn eq_ignoring_opgroup(&self, other: &Self) -> bool {
+        self.op == other.op && self.args == other.args
+    }
+}
+
+impl<P: PartialEq> SerialCircuit<P> {
+    /// Returns `true` if the circuits are equal, ignoring the
+    /// [`opgroup`](Command::opgroup) labels of their commands.
+    ///
+    /// See [`Command::eq_ignoring_opgroup`].
+    pub fn eq_ignoring_opgroups(&self, other: &Self) -> bool {
+        let SerialCircuit {
+            name,
+            phase,
+            commands,
+            qubits,
+            bits,
+            implicit_permutation,
+            number_of_ws,
+            number_of_rs,
+            created_qubits,
+            discarded_qubits,
+            schema_version,
+        } = self;
+        commands.len() == other.commands.len()
+            && commands
+                .iter()
+                .zip(&other.commands)
+                .all(|(a, b)| a.eq_ignoring_opgroup(b))
+            && name == &other.name
+            && phase == &other.phase
+            && qubits == &other.qubits
+            && bits == &other.bits
+            && implicit_permutation == &other.implicit_permutation
+            && number_of_ws == &other.number_of_ws
+            && number_of_rs == &other.number_of_rs
+            && created_qubits == &other.created_qubits
+            && discarded_qubits == &other.discarded_qubits
+            && schema_version == &other.schema_version
+    }
+}
+
+impl<P> SerialCircuit<P> {
+    /// Returns the identifiers of the qubits declared in the circuit.
+    pub(crate) fn qubit_ids(&self) -> HashSet<&ElementId> {
+        self.qubits.iter().map(|q| &q.id).collect()
+    }
+
+    /// Returns the identifiers of the bits declared in the circuit.
+    pub(crate) fn bit_ids(&self) -> HashSet<&ElementId> {
+        self.bits.iter().map(|b| &b.id).collect()
+    }
+}
+
+impl<P> Operation<P> {
+    /// Adds the type of this operation and of all nested operations to `gates`.
+    fn collect_op_types(&self, gates: &mut BTreeSet<OpType>) {
+        gates.insert(self.op_type);
+        if let Some(conditional) = &self.conditional {
+            conditional.op.collect_op_types(gates);
+        }
+        if let Some(Classical::MultiBit { op, .. }) = self.classical.as_deref() {
+            op.collect_op_types(gates);
+        }
+        if let Some(op_box) = &self.op_box {
+            for circuit in op_box.circuits() {
+                gates.extend(circuit.gate_set_recursive());
+            }
+            for op in op_box.operations() {
+                op.collect_op_types(gates);
+            }
+        }
+    }
+}
+
+impl<P: AsRef<str>> SerialCircuit<P> {
+    /// Returns the free symbols used in the circuit's parameters.
+    ///
+    /// This includes the global phase, the parameters of every operation, and
+    /// the symbolic expressions stored in nested boxes and circuits.
+    pub fn free_symbols(&self) -> BTreeSet<String> {
+        let mut symbols = BTreeSet::new();
+        self.visit_params(&mut |expr| {
+            symbols.extend(param::free_symbols(expr).into_iter().map(String::from))
+        });
+        symbols
+    }
+
+    /// Returns the number of T gates in the circuit, including inside boxes
+    /// and conditionals.
+    ///
+    /// Both [`OpType::T`] and [`OpType::Tdg`] are counted, as are the
+    /// T-equivalent [`OpType::Rz`] and [`OpType::U1`] gates whose angle is an
+    /// odd multiple of 1/4.
+    pub fn t_count(&self) -> usize {
+        self.count_ops(&is_t_like)
+    }
+
+    /// Returns the number of single-qubit rotations in the circuit that need
+    /// approximate synthesis in a fault-tolerant gate set, including inside
+    /// boxes and conditionals.
+    ///
+    /// These are the [`OpType::Rx`], [`OpType::Ry`], [`OpType::Rz`] and
+    /// [`OpType::U1`] gates whose angle is not a multiple of 1/4, including
+    /// those with symbolic angles. Rotations by multiples of 1/4 are Clifford
+    /// or T-equivalent, see [`SerialCircuit::t_count`].
+    pub fn rotation_count(&self) -> usize {
+        self.count_ops(&|op_type, params| {
+            matches!(op_type, OpType::Rx | OpType::Ry | OpType::Rz | OpType::U1)
+                && !params.iter().all(|p| eighth_turns(p).is_some())
+        })
+    }
+
+    /// Returns the number of operations in the circuit, including nested
+    /// ones, for which `pred` holds given their type and parameters.
+    fn count_ops(&self, pred: &dyn Fn(OpType, &[&str]) -> bool) -> usize {
+        self.commands.iter().map(|c| c.op.count_ops(pred)).sum()
+    }
+
+    /// Calls `f` on the global phase and every parameter in the circuit.
+    fn visit_params(&self, f: &mut dyn FnMut(&str)) {
+        f(self.phase.as_ref());
+        for command in &self.commands {
+            command.op.visit_params(f);
+        }
+    }
+}
+
+impl<P: AsRef<str>> Operation<P> {
+    /// Returns the number of operations among this one and its nested
+    /// operations for which `pred` holds.
+    fn count_ops(&self, pred: &dyn Fn(OpType, &[&str]) -> bool) -> usize {
+        let params: Vec<&str> = self.params().iter().map(AsRef::as_ref).collect();
+        let mut count = usize::from(pred(self.op_type, &params));
+        if let Some(conditional) = &self.conditional {
+            count += conditional.op.count_ops(pred);
+        }
+        if let Some(Classical::MultiBit { op, .. }) = self.classical.as_deref() {
+            count += op.count_ops(pred);
+        }
+        if let Some(op_box) = &self.op_box {
+            count += op_box
+                .circuits()
+                .iter()
+                .map(|c| c.count_ops(pred))
+                .sum::<usize>();
+            count += op_box
+                .operations()
+                .iter()
+                .map(|op| op.count_ops(pred))
+                .sum::<usize>();
+        }
+        count
+    }
+
+    /// Calls `f` on every parameter of the operation and its nested
+    /// operations.
+    fn visit_params(&self, f: &mut dyn FnMut(&str)) {
+        for param in self.params.iter().flatten() {
+            f(param.as_ref());
+        }
+        if let Some(conditional) = &self.conditional {
+            conditional.op.visit_params(f);
+        }
+        if let Some(Classical::MultiBit { op, .. }) = self.classical.as_deref() {
+            op.visit_params(f);
+        }
+        let Some(op_box) = &self.op_box else {
+            return;
+        };
+        for param in op_box.params() {
+            f(param);
+        }
+        for circuit in op_box.circuits() {
+            circuit.visit_params(f);
+        }
+        for op in op_box.operations() {
+            op.visit_params(f);
+        }
+    }
+}
+
+/// Returns `true` for T gates and their equivalent Z rotations.
+fn is_t_like(op_type: OpType, params: &[&str]) -> bool {
+    match (op_type, params) {
+        (OpType::T | OpType::Tdg, _) => true,
+        (OpType::Rz | OpType::U1, [angle]) => eighth_turns(angle).is_some_and(|k| k % 2 != 0),
+        _ => false,
+    }
+}
+
+/// Returns an angle given in half-turns as a whole number of eighths of a
+/// turn, if it is numeric and such a multiple.
+fn eighth_turns(angle: &str) -> Option<i64> {
+    let value = angle.trim().parse::<f64>().ok()?;
+    let eighths = 4.0 * value;
+    let rounded = eighths.round();
+    ((eighths - rounded).abs() < 1e-10 && rounded.is_finite()).then_some(rounded as i64)
+}
@@ -0,0 +1,93 @@
+//! Incremental construction of serialized circuits.
+
+use super::{Command, Operation, SerialCircuit};
+use crate::optype::OpType;
+use crate::register::{Bit, ElementId, Qubit};
+
+/// A builder for [`SerialCircuit`]s.
+///
+/// Registers are declared up front, and commands are then appended in order.
+/// The arguments of the commands are not checked against the declared
+/// registers, see [`SerialCircuit::validate`] for structural checks on the
+/// finished circuit.
+///
+/// ```
+/// use tket_json_rs::circuit_json::{CircuitBuilder, Operation};
+/// use tket_json_rs::register::ElementId;
+/// use tket_json_rs::OpType;
+///
+/// let q = |i| ElementId("q".to_string(), vec![i]);
+/// let mut builder = CircuitBuilder::new();
+/// builder
+///     .add_qubit_register("q", 2)
+///     .apply(Operation::from_optype(OpType::H), &[q(0)])
+///     .apply(Operation::from_optype(OpType::CX), &[q(0), q(1)]);
+/// let circuit = builder.finish();
+/// assert_eq!(circuit.qubits.len(), 2);
+/// assert_eq!(circuit.commands.len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircuitBuilder {
+    /// The circuit under construction.
+    circuit: SerialCircuit,
+}
+
+impl Default for CircuitBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircuitBuilder {
+    /// Creates a builder for an empty circuit with no global phase.
+    pub fn new() -> Self {
+        Self {
+            circuit: SerialCircuit::new(None, "0".to_string()),
+        }
+    }
+
+    /// Sets the name of the circuit.
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.circuit.name = Some(name.into());
+        self
+    }
+
+    /// Declares a quantum register with qubits `name[0]` to `name[size - 1]`.
+    pub fn add_qubit_register(&mut self, name: &str, size: u32) -> &mut Self {
+        let qubits = register(name, size).map(|id| Qubit { id });
+        self.circuit.qubits.extend(qubits);
+        self
+    }
+
+    /// Declares a classical register with bits `name[0]` to `name[size - 1]`.
+    pub fn add_bit_register(&mut self, name: &str, size: u32) -> &mut Self {
+        let bits = register(name, size).map(|id| Bit { id });
+        self.circuit.bits.extend(bits);
+        self
+    }
+
+    /// Appends a command applying `op` to `args`.
+    pub fn apply(&mut self, op: Operation, args: &[ElementId]) -> &mut Self {
+        self.circuit.commands.push(Command {
+            op,
+            args: args.to_vec(),
+            opgroup: None,
+        });
+        self
+    }
+
+    /// Appends a measurement of `qubit` into `bit`.
+    pub fn measure(&mut self, qubit: ElementId, bit: ElementId) -> &mut Self {
+        self.apply(Operation::from_optype(OpType::Measure), &[qubit, bit])
+    }
+
+    /// Returns the constructed circuit.
+    pub fn finish(self) -> SerialCircuit {
+        self.circuit
+    }
+}
+
+/// Returns the identifiers of the elements of a register.
+fn register(name: &str, size: u32) -> impl Iterator<Item = ElementId> + '_ {
+    (0..size).map(move |i| ElementId(name.to_string(), vec![i64::from(i)]))
+}
@@ -0,0 +1,38 @@
+//! Graphviz export of serialized circuits.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::SerialCircuit;
+use crate::register::ElementId;
+
+impl<P> SerialCircuit<P> {
+    /// Returns the dependency graph of the circuit in Graphviz DOT format.
+    ///
+    /// Each command is a node labelled with its operation type and arguments.
+    /// An edge labelled with a qubit or bit connects each command to the next
+    /// command acting on that unit, so commands sharing several units are
+    /// connected by several edges.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        let mut last_command: HashMap<&ElementId, usize> = HashMap::new();
+        for (index, command) in self.commands.iter().enumerate() {
+            let args: Vec<String> = command.args.iter().map(ElementId::to_string).collect();
+            let label = format!("{}({})", command.op.op_type, args.join(", "));
+            writeln!(dot, "    {index} [label={}];", escape(&label)).unwrap();
+            for arg in &command.args {
+                if let Some(previous) = last_command.insert(arg, index) {
+                    let label = escape(&arg.to_string());
+                    writeln!(dot, "    {previous} -> {index} [label={label}];").unwrap();
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Quotes a string as a DOT identifier.
+fn escape(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,305 @@
+//! Pauli frame propagation through Clifford circuits.
+
+use std::collections::HashMap;
+
+use super::SerialCircuit;
+use crate::optype::OpType;
+use crate::register::ElementId;
+
+/// A single-qubit Pauli operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pauli {
+    /// The identity.
+    I,
+    /// The Pauli X operator.
+    X,
+    /// The Pauli Y operator.
+    Y,
+    /// The Pauli Z operator.
+    Z,
+}
+
+impl Pauli {
+    /// Returns the X and Z components of the operator, with `Y` having both.
+    fn bits(self) -> (bool, bool) {
+        match self {
+            Pauli::I => (false, false),
+            Pauli::X => (true, false),
+            Pauli::Y => (true, true),
+            Pauli::Z => (false, true),
+        }
+    }
+
+    /// Inverse of [`Pauli::bits`].
+    fn from_bits(x: bool, z: bool) -> Self {
+        match (x, z) {
+            (false, false) => Pauli::I,
+            (true, false) => Pauli::X,
+            (true, true) => Pauli::Y,
+            (false, true) => Pauli::Z,
+        }
+    }
+}
+
+/// A signed tensor product of Pauli operators.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PauliString {
+    /// The operator on each qubit, in the declaration order of the circuit.
+    pub paulis: Vec<Pauli>,
+    /// Whether the product has a `-1` sign.
+    pub negative: bool,
+}
+
+/// The action of a Clifford circuit on the Pauli operators, by conjugation.
+///
+/// For a circuit implementing the unitary `U`, the frame records the images
+/// `U X_q U†` and `U Z_q U†` for every qubit `q`, which determine the image
+/// of any Pauli operator. The image of `X_q` is the operator obtained by
+/// propagating an X error on `q` from the start of the circuit to its end.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PauliFrame {
+    /// The qubits of the circuit, in declaration order.
+    pub qubits: Vec<ElementId>,
+    /// The image of the X operator on each qubit.
+    pub x_images: Vec<PauliString>,
+    /// The image of the Z operator on each qubit.
+    pub z_images: Vec<PauliString>,
+}
+
+impl PauliFrame {
+    /// Returns the image of the X operator on `qubit`.
+    pub fn x_image(&self, qubit: &ElementId) -> Option<&PauliString> {
+        let index = self.qubits.iter().position(|q| q == qubit)?;
+        Some(&self.x_images[index])
+    }
+
+    /// Returns the image of the Z operator on `qubit`.
+    pub fn z_image(&self, qubit: &ElementId) -> Option<&PauliString> {
+        let index = self.qubits.iter().position(|q| q == qubit)?;
+        Some(&self.z_images[index])
+    }
+
+    /// Returns the identity frame on `qubits`.
+    fn identity(qubits: Vec<ElementId>) -> Self {
+        let single = |index: usize, pauli: Pauli| PauliString {
+            paulis: (0..qubits.len())
+                .map(|i| if i == index { pauli } else { Pauli::I })
+                .collect(),
+            negative: false,
+        };
+        let x_images = (0..qubits.len()).map(|i| single(i, Pauli::X)).collect();
+        let z_images = (0..qubits.len()).map(|i| single(i, Pauli::Z)).collect();
+        Self {
+            qubits,
+            x_images,
+            z_images,
+        }
+    }
+
+    /// Updates every image with a function of its sign and of the X and Z
+    /// components of the operators on qubits `a` and `b`.
+    ///
+    /// For single-qubit updates `a` and `b` are equal, and only the first two
+    /// components are written back.
+    fn update(&mut self, a: usize, b: usize, f: impl Fn(&mut bool, &mut [bool; 4])) {
+        for image in self.x_images.iter_mut().chain(self.z_images.iter_mut()) {
+            let (xa, za) = image.paulis[a].bits();
+            let (xb, zb) = image.paulis[b].bits();
+            let mut bits = [xa, za, xb, zb];
+            f(&mut image.negative, &mut bits);
+            image.paulis[a] = Pauli::from_bits(bits[0], bits[1]);
+            if a != b {
+                image.paulis[b] = Pauli::from_bits(bits[2], bits[3]);
+            }
+        }
+    }
+
+    /// Conjugates by a Hadamard gate.
+    fn h(&mut self, a: usize) {
+        self.update(a, a, |negative, [x, z, ..]| {
+            *negative ^= *x && *z;
+            std::mem::swap(x, z);
+        });
+    }
+
+    /// Conjugates by `S^k`.
+    fn s(&mut self, a: usize, k: u8) {
+        for _ in 0..k % 4 {
+            self.update(a, a, |negative, [x, z, ..]| {
+                *negative ^= *x && *z;
+                *z ^= *x;
+            });
+        }
+    }
+
+    /// Conjugates by a CX gate.
+    fn cx(&mut self, a: usize, b: usize) {
+        self.update(a, b, |negative, [xa, za, xb, zb]| {
+            *negative ^= *xa && *zb && !(*xb ^ *za);
+            *xb ^= *xa;
+            *za ^= *zb;
+        });
+    }
+
+    /// Conjugates by a CZ gate.
+    fn cz(&mut self, a: usize, b: usize) {
+        self.h(b);
+        self.cx(a, b);
+        self.h(b);
+    }
+
+    /// Conjugates by `Rx(k/2)`.
+    fn rx(&mut self, a: usize, k: u8) {
+        self.h(a);
+        self.s(a, k);
+        self.h(a);
+    }
+
+    /// Conjugates by `ZZPhase(k/2)`.
+    fn zz(&mut self, a: usize, b: usize, k: u8) {
+        for _ in 0..k % 4 {
+            self.cz(a, b);
+            self.s(a, 1);
+            self.s(b, 1);
+        }
+    }
+
+    /// Conjugates by `XXPhase(k/2)`.
+    fn xx(&mut self, a: usize, b: usize, k: u8) {
+        self.h(a);
+        self.h(b);
+        self.zz(a, b, k);
+        self.h(a);
+        self.h(b);
+    }
+
+    /// Conjugates by `YYPhase(k/2)`.
+    fn yy(&mut self, a: usize, b: usize, k: u8) {
+        self.s(a, 3);
+        self.s(b, 3);
+        self.xx(a, b, k);
+        self.s(a, 1);
+        self.s(b, 1);
+    }
+
+    /// Conjugates by a Clifford gate, given the indices of its qubits and its
+    /// angles as multiples of a quarter turn.
+    ///
+    /// Returns `None` for gates that are not supported.
+    fn apply(&mut self, op_type: OpType, q: &[usize], k: &[u8]) -> Option<()> {
+        match (op_type, q, k) {
+            (OpType::noop | OpType::Phase, _, _) => {}
+            (OpType::H, &[a], _) => self.h(a),
+            (OpType::S, &[a], _) => self.s(a, 1),
+            (OpType::Sdg, &[a], _) => self.s(a, 3),
+            (OpType::Z, &[a], _) => self.s(a, 2),
+            (OpType::X, &[a], _) => self.rx(a, 2),
+            (OpType::Y, &[a], _) => {
+                self.s(a, 2);
+                self.rx(a, 2);
+            }
+            (OpType::V | OpType::SX, &[a], _) => self.rx(a, 1),
+            (OpType::Vdg | OpType::SXdg, &[a], _) => self.rx(a, 3),
+            (OpType::Rz | OpType::U1, &[a], &[t]) => self.s(a, t),
+            (OpType::Rx, &[a], &[t]) => self.rx(a, t),
+            (OpType::Ry, &[a], &[t]) => {
+                self.s(a, 3);
+                self.rx(a, t);
+                self.s(a, 1);
+            }
+            (OpType::TK1, &[a], &[t0, t1, t2]) => {
+                self.s(a, t2);
+                self.rx(a, t1);
+                self.s(a, t0);
+            }
+            (OpType::CX, &[a, b], _) => self.cx(a, b),
+            (OpType::CY, &[a, b], _) => {
+                self.s(b, 3);
+                self.cx(a, b);
+                self.s(b, 1);
+            }
+            (OpType::CZ, &[a, b], _) => self.cz(a, b),
+            (OpType::SWAP, &[a, b], _) => {
+                self.cx(a, b);
+                self.cx(b, a);
+                self.cx(a, b);
+            }
+            (OpType::ZZMax, &[a, b], _) => self.zz(a, b, 1),
+            (OpType::ECR, &[a, b], _) => {
+                self.h(b);
+                self.zz(a, b, 1);
+                self.h(b);
+                self.rx(a, 2);
+            }
+            (OpType::ISWAPMax, &[a, b], _) => {
+                self.xx(a, b, 3);
+                self.yy(a, b, 3);
+            }
+            (OpType::ZZPhase, &[a, b], &[t]) => self.zz(a, b, t),
+            (OpType::XXPhase, &[a, b], &[t]) => self.xx(a, b, t),
+            (OpType::YYPhase, &[a, b], &[t]) => self.yy(a, b, t),
+            (OpType::TK2, &[a, b], &[t0, t1, t2]) => {
+                self.xx(a, b, t0);
+                self.yy(a, b, t1);
+                self.zz(a, b, t2);
+            }
+            (OpType::PhaseGadget, q, &[t]) => {
+                let Some((&last, _)) = q.split_last() else {
+                    return Some(());
+                };
+                for pair in q.windows(2) {
+                    self.cx(pair[0], pair[1]);
+                }
+                self.s(last, t);
+                for pair in q.windows(2).rev() {
+                    self.cx(pair[0], pair[1]);
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+impl<P: AsRef<str>> SerialCircuit<P> {
+    /// Returns the action of the circuit on the Pauli operators, if every
+    /// command is a Clifford gate.
+    ///
+    /// Returns `None` if any command is not recognised as Clifford by
+    /// [`OpType::is_clifford`], including measurements, conditional
+    /// operations, boxes and gates with symbolic parameters. Barriers are
+    /// ignored. The implicit permutation of the circuit is not applied.
+    pub fn pauli_frame(&self) -> Option<PauliFrame> {
+        let qubits: Vec<ElementId> = self.qubits.iter().map(|q| q.id.clone()).collect();
+        let index: HashMap<&ElementId, usize> =
+            qubits.iter().enumerate().map(|(i, q)| (q, i)).collect();
+        let mut frame = PauliFrame::identity(qubits.clone());
+        for command in &self.commands {
+            let op_type = command.op.op_type;
+            if op_type == OpType::Barrier {
+                continue;
+            }
+            let params = command.op.params();
+            if !op_type.is_clifford(params) {
+                return None;
+            }
+            let args = command
+                .args
+                .iter()
+                .map(|arg| index.get(arg).copied())
+                .collect::<Option<Vec<usize>>>()?;
+            // `is_clifford` has checked that the angles are multiples of 1/2.
+            let quarter_turns: Vec<u8> = params
+                .iter()
+                .map(|p| {
+                    let value: f64 = p.as_ref().trim().parse().unwrap_or_default();
+                    (2.0 * value).round().rem_euclid(4.0) as u8
+                })
+                .collect();
+            frame.apply(op_type, &args, &quarter_turns)?;
+        }
+        Some(frame)
+    }
+}
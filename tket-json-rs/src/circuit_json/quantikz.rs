@@ -0,0 +1,126 @@
+//! LaTeX `quantikz` export of serialized circuits.
+
+use std::collections::HashMap;
+
+use super::{Command, SerialCircuit};
+use crate::optype::OpType;
+use crate::register::ElementId;
+
+impl<P: AsRef<str>> SerialCircuit<P> {
+    /// Returns the circuit as a `quantikz` LaTeX environment.
+    ///
+    /// Each declared qubit and bit is a row of the diagram, and each of the
+    /// circuit's [`layers`](SerialCircuit::layers) is a column. Controlled
+    /// gates, swaps and measurements are drawn with the dedicated `quantikz`
+    /// commands, and barriers are omitted. Any other operation, including
+    /// boxes and conditionals, is drawn as a labelled `\gate` placeholder on
+    /// each of its qubits.
+    pub fn to_quantikz(&self) -> String {
+        let rows: Vec<(&ElementId, &str)> = self
+            .qubits
+            .iter()
+            .map(|q| (&q.id, r"\qw"))
+            .chain(self.bits.iter().map(|b| (&b.id, r"\cw")))
+            .collect();
+        let row_of: HashMap<&ElementId, usize> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (*id, i))
+            .collect();
+
+        let layers = self.layers();
+        let mut grid: Vec<Vec<String>> = rows
+            .iter()
+            .map(|(_, wire)| vec![wire.to_string(); layers.len()])
+            .collect();
+        for (column, layer) in layers.iter().enumerate() {
+            for &index in layer {
+                let command = &self.commands[index];
+                let args: Vec<usize> = command
+                    .args
+                    .iter()
+                    .filter_map(|arg| row_of.get(arg).copied())
+                    .collect();
+                if args.len() != command.args.len() {
+                    continue;
+                }
+                for (row, cell) in command_cells(command, &args, self.qubits.len()) {
+                    grid[row][column] = cell;
+                }
+            }
+        }
+
+        let lines: Vec<String> = rows
+            .iter()
+            .zip(grid)
+            .map(|((id, wire), cells)| {
+                let mut line = format!(r"\lstick{{{}}}", escape(&id.to_string()));
+                for cell in cells.iter().map(String::as_str).chain([*wire]) {
+                    line.push_str(" & ");
+                    line.push_str(cell);
+                }
+                line
+            })
+            .collect();
+        format!(
+            "\\begin{{quantikz}}\n{}\n\\end{{quantikz}}\n",
+            lines.join(" \\\\\n")
+        )
+    }
+}
+
+/// Returns the cells drawing `command`, as pairs of a row and its content.
+///
+/// `rows` are the diagram rows of the command's arguments, where the first
+/// `n_qubits` rows correspond to qubits.
+fn command_cells<P: AsRef<str>>(
+    command: &Command<P>,
+    rows: &[usize],
+    n_qubits: usize,
+) -> Vec<(usize, String)> {
+    let op = &command.op;
+    let offset = |from: usize, to: usize| to as i64 - from as i64;
+    let controlled = |target: &str| {
+        let (&target_row, controls) = rows.split_last().unwrap();
+        controls
+            .iter()
+            .map(|&row| (row, format!(r"\ctrl{{{}}}", offset(row, target_row))))
+            .chain([(target_row, target.to_string())])
+            .collect()
+    };
+    match (op.op_type, rows) {
+        (OpType::CX | OpType::CCX, [_, _, ..]) => controlled(r"\targ{}"),
+        (OpType::CY, [_, _]) => controlled(r"\gate{Y}"),
+        (OpType::CZ, [_, _]) => controlled(r"\control{}"),
+        (OpType::SWAP, &[a, b]) => vec![
+            (a, format!(r"\swap{{{}}}", offset(a, b))),
+            (b, r"\targX{}".to_string()),
+        ],
+        (OpType::Measure, &[qubit, _]) => vec![(qubit, r"\meter{}".to_string())],
+        (OpType::Barrier, _) => Vec::new(),
+        _ => {
+            let mut label = op.op_type.to_string();
+            let params: Vec<&str> = op.params.iter().flatten().map(AsRef::as_ref).collect();
+            if !params.is_empty() {
+                label = format!("{label}({})", params.join(", "));
+            }
+            let cell = format!(r"\gate{{{}}}", escape(&label));
+            rows.iter()
+                .filter(|&&row| row < n_qubits)
+                .map(|&row| (row, cell.clone()))
+                .collect()
+        }
+    }
+}
+
+/// Escapes the characters of `s` that have a special meaning in LaTeX.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '_' | '&' | '%' | '#' | '$') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,102 @@
+//! Export of stabilizer circuits to the [Stim](https://github.com/quantumlib/Stim)
+//! circuit format.
+
+use std::collections::HashMap;
+
+use derive_more::{Display, Error};
+
+use super::SerialCircuit;
+use crate::optype::OpType;
+use crate::register::ElementId;
+
+/// Error returned by [`SerialCircuit::to_stim`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum StimError {
+    /// The operation has no Stim equivalent.
+    #[display("command {index} applies an unsupported {op_type} operation")]
+    UnsupportedOperation {
+        /// The index of the offending command.
+        index: usize,
+        /// The type of the operation.
+        op_type: OpType,
+    },
+    /// A command refers to a qubit that is not declared.
+    #[display("command {index} acts on the undeclared qubit {unit}")]
+    UndeclaredUnit {
+        /// The index of the offending command.
+        index: usize,
+        /// The undeclared unit.
+        unit: ElementId,
+    },
+    /// A command has a different number of arguments than its operation
+    /// takes.
+    #[display("command {index} has {found} arguments, but its operation takes {expected}")]
+    ArityMismatch {
+        /// The index of the offending command.
+        index: usize,
+        /// The number of arguments the operation takes.
+        expected: usize,
+        /// The number of arguments of the command.
+        found: usize,
+    },
+}
+
+impl<P> SerialCircuit<P> {
+    /// Returns the circuit in the Stim circuit format, one instruction per
+    /// line.
+    ///
+    /// Qubits are numbered in declaration order. Only the Clifford gates with a
+    /// direct Stim equivalent, measurements and resets are supported, and
+    /// barriers are omitted. Stim appends measurement results to its own
+    /// record, so the bits targeted by measurements are not kept.
+    pub fn to_stim(&self) -> Result<String, StimError> {
+        let qubits: HashMap<&ElementId, usize> = self
+            .qubits
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (&q.id, i))
+            .collect();
+
+        let mut stim = String::new();
+        for (index, command) in self.commands.iter().enumerate() {
+            let op_type = command.op.op_type;
+            let (name, n_qubits) = match op_type {
+                OpType::Barrier => continue,
+                OpType::noop => ("I", 1),
+                OpType::H => ("H", 1),
+                OpType::S => ("S", 1),
+                OpType::Sdg => ("S_DAG", 1),
+                OpType::X => ("X", 1),
+                OpType::Y => ("Y", 1),
+                OpType::Z => ("Z", 1),
+                OpType::CX => ("CX", 2),
+                OpType::CY => ("CY", 2),
+                OpType::CZ => ("CZ", 2),
+                OpType::SWAP => ("SWAP", 2),
+                OpType::Measure => ("M", 1),
+                OpType::Reset => ("R", 1),
+                _ => return Err(StimError::UnsupportedOperation { index, op_type }),
+            };
+            // Measurements also take the bit they write to.
+            let expected = n_qubits + usize::from(op_type == OpType::Measure);
+            if command.args.len() != expected {
+                return Err(StimError::ArityMismatch {
+                    index,
+                    expected,
+                    found: command.args.len(),
+                });
+            }
+            stim.push_str(name);
+            for arg in &command.args[..n_qubits] {
+                let qubit = qubits.get(arg).ok_or_else(|| StimError::UndeclaredUnit {
+                    index,
+                    unit: arg.clone(),
+                })?;
+                stim.push_str(&format!(" {qubit}"));
+            }
+            stim.push('\n');
+        }
+        Ok(stim)
+    }
+}
@@ -0,0 +1,107 @@
+//! Tensor network descriptions of serialized circuits.
+
+use std::collections::HashMap;
+
+use super::{Operation, SerialCircuit};
+use crate::register::ElementId;
+
+/// The structure of a circuit viewed as a tensor network.
+///
+/// Every qubit wire segment between two commands is an index, identified by
+/// a number in `0..n_indices`. Each command contributes one tensor, and each
+/// index is shared by exactly two legs: those of the tensors it connects, or
+/// of the open [`inputs`](Self::inputs) and [`outputs`](Self::outputs) of the
+/// circuit.
+///
+/// Only the connectivity is described. The tensor entries are left to the
+/// consumer, which can derive them from each tensor's operation.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct TensorNetwork<P = String> {
+    /// The tensors of the network, in command order.
+    pub tensors: Vec<Tensor<P>>,
+    /// The index of the input leg of each qubit, in declaration order.
+    pub inputs: Vec<(ElementId, usize)>,
+    /// The index of the output leg of each qubit, in declaration order.
+    pub outputs: Vec<(ElementId, usize)>,
+    /// The number of indices in the network.
+    pub n_indices: usize,
+}
+
+/// A tensor in a [`TensorNetwork`], corresponding to a command.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Tensor<P = String> {
+    /// The index of the command in the circuit.
+    pub command: usize,
+    /// The operation applied by the command.
+    pub op: Operation<P>,
+    /// The input indices of the tensor, one per qubit argument.
+    pub inputs: Vec<usize>,
+    /// The output indices of the tensor, in the same order as the inputs.
+    pub outputs: Vec<usize>,
+}
+
+impl<P> Tensor<P> {
+    /// Returns the number of legs of the tensor.
+    pub fn rank(&self) -> usize {
+        self.inputs.len() + self.outputs.len()
+    }
+}
+
+impl<P: Clone> SerialCircuit<P> {
+    /// Returns the tensor network structure of the circuit, without
+    /// contracting it.
+    ///
+    /// Each command acting on qubits becomes a tensor with one input and one
+    /// output leg per qubit argument. Bit arguments carry no quantum data and
+    /// are not represented, and commands acting only on bits are omitted.
+    pub fn to_tensor_network(&self) -> TensorNetwork<P> {
+        let inputs: Vec<(ElementId, usize)> = self
+            .qubits
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (q.id.clone(), i))
+            .collect();
+        let mut current: HashMap<&ElementId, usize> =
+            inputs.iter().map(|(id, i)| (id, *i)).collect();
+        let mut n_indices = inputs.len();
+
+        let mut tensors = Vec::new();
+        for (index, command) in self.commands.iter().enumerate() {
+            let qubits: Vec<&ElementId> = command
+                .args
+                .iter()
+                .filter(|arg| current.contains_key(arg))
+                .collect();
+            if qubits.is_empty() {
+                continue;
+            }
+            let mut tensor = Tensor {
+                command: index,
+                op: command.op.clone(),
+                inputs: Vec::with_capacity(qubits.len()),
+                outputs: Vec::with_capacity(qubits.len()),
+            };
+            for qubit in qubits {
+                tensor.inputs.push(current[qubit]);
+                tensor.outputs.push(n_indices);
+                current.insert(qubit, n_indices);
+                n_indices += 1;
+            }
+            tensors.push(tensor);
+        }
+
+        let outputs = self
+            .qubits
+            .iter()
+            .map(|q| (q.id.clone(), current[&q.id]))
+            .collect();
+        TensorNetwork {
+            tensors,
+            inputs,
+            outputs,
+            n_indices,
+        }
+    }
+}
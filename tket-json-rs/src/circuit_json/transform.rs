@@ -0,0 +1,1070 @@
+//! Rewrites of serialized circuits.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use derive_more::{Display, Error};
+
+use super::{Command, ImplicitPermutation, Operation, SerialCircuit};
+use crate::opbox::OpBox;
+use crate::optype::OpType;
+use crate::param;
+use crate::register::{Bit, ElementId, Qubit};
+
+/// Error returned by [`SerialCircuit::strict_subcircuit`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum SubcircuitError {
+    /// A command acts on qubits both inside and outside the selected subset.
+    #[display("command {index} acts on qubit {qubit}, outside of the selected subset")]
+    CommandCrossesBoundary {
+        /// The index of the offending command.
+        index: usize,
+        /// A qubit outside the subset that the command acts on.
+        qubit: ElementId,
+    },
+}
+
+/// Error returned by [`SerialCircuit::replace_gate`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum ReplaceError {
+    /// A command to replace acts on a different number of units than the
+    /// replacement circuit declares.
+    #[display("command {index} acts on {found} units, but the replacement circuit has {expected}")]
+    ArityMismatch {
+        /// The index of the offending command.
+        index: usize,
+        /// The number of qubits and bits declared by the replacement circuit.
+        expected: usize,
+        /// The number of arguments of the command.
+        found: usize,
+    },
+    /// A command to replace has parameters, which the replacement circuit
+    /// cannot take.
+    #[display("command {index} has parameters, which the replacement would drop")]
+    ParameterisedCommand {
+        /// The index of the offending command.
+        index: usize,
+    },
+    /// The replacement circuit acts on a unit it does not declare, which
+    /// cannot be mapped onto the arguments of the replaced commands.
+    #[display("the replacement circuit acts on the undeclared unit {unit}")]
+    UnmappedUnit {
+        /// The undeclared unit.
+        unit: ElementId,
+    },
+}
+
+/// Error returned by [`SerialCircuit::permute_qubits`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum PermutationError {
+    /// The permutation refers to a qubit that is not declared in the circuit.
+    #[display("qubit {qubit} is not declared in the circuit")]
+    UnknownQubit {
+        /// The undeclared qubit.
+        qubit: ElementId,
+    },
+    /// The map is not a bijection on the qubits it moves.
+    #[display("qubit {qubit} is the image of more than one qubit")]
+    NotBijective {
+        /// A qubit that would end up referenced by two different qubits.
+        qubit: ElementId,
+    },
+}
+
+/// Error returned by [`SerialCircuit::dagger`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum DaggerError {
+    /// A command applies an operation whose inverse is not known.
+    #[display("command {index} applies a non-invertible {op_type} operation")]
+    NotInvertible {
+        /// The index of the offending command.
+        index: usize,
+        /// The type of the operation.
+        op_type: OpType,
+    },
+    /// The circuit has a non-trivial implicit permutation.
+    #[display("circuits with an implicit qubit permutation cannot be inverted")]
+    ImplicitPermutation,
+}
+
+/// Error returned by [`SerialCircuit::conjugate_by`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum ConjugateError {
+    /// The compute circuit cannot be inverted.
+    #[display("{_0}")]
+    Dagger(DaggerError),
+    /// The compute circuit acts on a unit that is not declared in the circuit.
+    #[display("the compute circuit acts on the undeclared unit {unit}")]
+    UndeclaredUnit {
+        /// The undeclared unit.
+        unit: ElementId,
+    },
+}
+
+/// Error returned by [`SerialCircuit::pad_with_identity`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum PadError {
+    /// The padding operation is not a single-qubit identity gate.
+    #[display("{op_type} is not a single-qubit identity gate")]
+    NotIdentityGate {
+        /// The rejected operation type.
+        op_type: OpType,
+    },
+}
+
+/// Error returned by [`SerialCircuit::import_registers`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum RegisterError {
+    /// A register holds qubits in one circuit and bits in the other.
+    #[display("register {register} holds qubits in one circuit and bits in the other")]
+    KindMismatch {
+        /// The name of the register.
+        register: String,
+    },
+}
+
+/// Error returned by [`SerialCircuit::rebase_basic`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum RebaseError {
+    /// A command applies an operation with no known decomposition.
+    #[display(
+        "command {index} applies a {op_type} operation with no decomposition into CX, Rz and H"
+    )]
+    UnsupportedOperation {
+        /// The index of the offending command.
+        index: usize,
+        /// The type of the operation.
+        op_type: OpType,
+    },
+}
+
+/// Single-qubit gates acting as the identity, usable to pad idle qubits.
+const IDENTITY_GATES: &[OpType] = &[OpType::noop];
+
+impl<P> SerialCircuit<P> {
+    /// Sorts the qubit and bit declarations of the circuit.
+    ///
+    /// Registers are ordered by name, and elements within a register by
+    /// index. The commands are left untouched, so the semantics of the
+    /// circuit are preserved. Two circuits that only differ in the order of
+    /// their declarations serialize identically after canonicalization.
+    pub fn canonicalize(&mut self) {
+        self.qubits.sort();
+        self.bits.sort();
+    }
+
+    /// Reverses the order of the qubits within each register.
+    ///
+    /// In each quantum register, the qubit with the `i`-th smallest index is
+    /// exchanged with the qubit with the `i`-th largest index, and every
+    /// reference to them in the commands and permutations is updated. This
+    /// converts between big-endian and little-endian qubit orderings.
+    ///
+    /// Note that this changes the tensor order of the circuit's unitary, the
+    /// circuit is only equivalent to the original up to the reversal.
+    /// Operations nested inside boxes act on the box's own registers and
+    /// are left untouched.
+    pub fn reverse_qubit_order(&mut self) {
+        let mut registers: BTreeMap<&str, Vec<&ElementId>> = BTreeMap::new();
+        for qubit in &self.qubits {
+            registers.entry(&qubit.id.0).or_default().push(&qubit.id);
+        }
+        let mut mapping: HashMap<ElementId, ElementId> = HashMap::new();
+        for mut ids in registers.into_values() {
+            ids.sort();
+            for (&from, &to) in ids.iter().zip(ids.iter().rev()) {
+                mapping.insert(from.clone(), to.clone());
+            }
+        }
+
+        self.remap_qubits(&mapping);
+    }
+
+    /// Applies a permutation of the circuit's qubits.
+    ///
+    /// Every reference to a qubit in the commands, declarations and
+    /// permutations is replaced by its image under `perm`. Qubits that are
+    /// not keys of `perm` are left in place. The map must only refer to
+    /// declared qubits, and must be a bijection from its keys onto its
+    /// values; otherwise an error is returned and the circuit is left
+    /// untouched.
+    pub fn permute_qubits(
+        &mut self,
+        perm: &HashMap<ElementId, ElementId>,
+    ) -> Result<(), PermutationError> {
+        let declared = self.qubit_ids();
+        let mut pairs: Vec<_> = perm.iter().collect();
+        pairs.sort();
+        let mut images = HashSet::new();
+        for (from, to) in pairs {
+            if let Some(qubit) = [from, to].into_iter().find(|q| !declared.contains(q)) {
+                return Err(PermutationError::UnknownQubit {
+                    qubit: qubit.clone(),
+                });
+            }
+            if !images.insert(to) || !perm.contains_key(to) {
+                return Err(PermutationError::NotBijective { qubit: to.clone() });
+            }
+        }
+
+        self.remap_qubits(perm);
+        Ok(())
+    }
+
+    /// Replaces every reference to a qubit in `mapping` by its image.
+    fn remap_qubits(&mut self, mapping: &HashMap<ElementId, ElementId>) {
+        let remap = |id: &mut ElementId| {
+            if let Some(new_id) = mapping.get(id) {
+                *id = new_id.clone();
+            }
+        };
+        for qubit in &mut self.qubits {
+            remap(&mut qubit.id);
+        }
+        for command in &mut self.commands {
+            command.args.iter_mut().for_each(remap);
+        }
+        for ImplicitPermutation(from, to) in &mut self.implicit_permutation {
+            remap(&mut from.id);
+            remap(&mut to.id);
+        }
+        for qubit in self.created_qubits.iter_mut().flatten() {
+            remap(&mut qubit.id);
+        }
+        for qubit in self.discarded_qubits.iter_mut().flatten() {
+            remap(&mut qubit.id);
+        }
+    }
+
+    /// Declares every qubit and bit used by a command but missing from the
+    /// circuit's register lists.
+    ///
+    /// Undeclared units are appended after the existing declarations, in the
+    /// order in which they first appear in the commands. Whether an argument
+    /// is a qubit or a bit is inferred from the operation: its explicit
+    /// signature if present, the condition bits of conditional operations,
+    /// the target bit of measurements, and all the arguments of classical
+    /// operations.
+    pub fn materialize_registers(&mut self) {
+        let mut qubits: HashSet<ElementId> = self.qubits.iter().map(|q| q.id.clone()).collect();
+        let mut bits: HashSet<ElementId> = self.bits.iter().map(|b| b.id.clone()).collect();
+        for command in &self.commands {
+            for (i, arg) in command.args.iter().enumerate() {
+                if qubits.contains(arg) || bits.contains(arg) {
+                    continue;
+                }
+                if command.op.arg_is_bit(i) {
+                    bits.insert(arg.clone());
+                    self.bits.push(Bit { id: arg.clone() });
+                } else {
+                    qubits.insert(arg.clone());
+                    self.qubits.push(Qubit { id: arg.clone() });
+                }
+            }
+        }
+    }
+
+    /// Adds the qubit and bit declarations of `other` that are missing from
+    /// the circuit, without its commands.
+    ///
+    /// The imported units are appended in their order in `other`. Units
+    /// declared by both circuits are kept once.
+    ///
+    /// Returns an error, leaving the circuit unchanged, if a register name
+    /// is used for qubits in one circuit and for bits in the other.
+    pub fn import_registers<Q>(&mut self, other: &SerialCircuit<Q>) -> Result<(), RegisterError> {
+        let conflict = |qubits: &[Qubit], bits: &[Bit]| {
+            let names: HashSet<&str> = qubits.iter().map(|q| q.id.0.as_str()).collect();
+            bits.iter()
+                .map(|b| b.id.0.as_str())
+                .filter(|name| names.contains(name))
+                .min()
+                .map(str::to_string)
+        };
+        let conflict =
+            conflict(&self.qubits, &other.bits).or_else(|| conflict(&other.qubits, &self.bits));
+        if let Some(register) = conflict {
+            return Err(RegisterError::KindMismatch { register });
+        }
+
+        for qubit in &other.qubits {
+            if !self.qubits.contains(qubit) {
+                self.qubits.push(qubit.clone());
+            }
+        }
+        for bit in &other.bits {
+            if !self.bits.contains(bit) {
+                self.bits.push(bit.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the declarations of the qubits and bits that are not used.
+    ///
+    /// A unit is used if it is an argument of a command, or if it appears in
+    /// the created or discarded qubits, or in a non-trivial entry of the
+    /// implicit permutation. Trivial permutation entries of the removed
+    /// qubits are removed as well.
+    ///
+    /// Returns the number of removed qubits and bits.
+    pub fn prune_unused_units(&mut self) -> (usize, usize) {
+        let mut used: HashSet<ElementId> = self
+            .commands
+            .iter()
+            .flat_map(|c| c.args.iter().cloned())
+            .collect();
+        let listed = self
+            .created_qubits
+            .iter()
+            .flatten()
+            .map(|q| &q.id)
+            .chain(self.discarded_qubits.iter().flatten().map(|b| &b.id));
+        used.extend(listed.cloned());
+        for ImplicitPermutation(from, to) in &self.implicit_permutation {
+            if from != to {
+                used.insert(from.id.clone());
+                used.insert(to.id.clone());
+            }
+        }
+
+        let (n_qubits, n_bits) = (self.qubits.len(), self.bits.len());
+        self.qubits.retain(|q| used.contains(&q.id));
+        self.bits.retain(|b| used.contains(&b.id));
+        self.implicit_permutation
+            .retain(|ImplicitPermutation(from, _)| used.contains(&from.id));
+        (n_qubits - self.qubits.len(), n_bits - self.bits.len())
+    }
+}
+
+impl<P> SerialCircuit<P> {
+    /// Inserts a barrier across all qubits between consecutive layers of the
+    /// circuit.
+    ///
+    /// Layers are computed with [`SerialCircuit::layers`], and the commands
+    /// are reordered layer by layer. No barrier is added before the first or
+    /// after the last layer. The circuit's semantics are unchanged.
+    pub fn barrier_layers(&mut self) {
+        let layers = self.layers();
+        let qubits: Vec<ElementId> = self.qubits.iter().map(|q| q.id.clone()).collect();
+        let mut commands: Vec<Option<Command<P>>> = self.commands.drain(..).map(Some).collect();
+        for (i, layer) in layers.iter().enumerate() {
+            if i > 0 {
+                let mut barrier = Operation::from_optype(OpType::Barrier);
+                barrier.signature = Some(vec!["Q".to_string(); qubits.len()]);
+                self.commands.push(Command {
+                    op: barrier,
+                    args: qubits.clone(),
+                    opgroup: None,
+                });
+            }
+            self.commands
+                .extend(layer.iter().filter_map(|&index| commands[index].take()));
+        }
+    }
+
+    /// Inserts a `target` gate on every qubit that is idle in a layer of the
+    /// circuit.
+    ///
+    /// Layers are computed with [`SerialCircuit::layers`], and the commands
+    /// are reordered layer by layer, each followed by the padding gates on
+    /// the declared qubits it does not act on. Only gates acting as the
+    /// identity, such as [`OpType::noop`], are accepted as `target`, so the
+    /// padding does not change the circuit's semantics.
+    ///
+    /// Returns an error, leaving the circuit unchanged, if `target` is not
+    /// such a gate.
+    pub fn pad_with_identity(&mut self, target: OpType) -> Result<(), PadError> {
+        if !IDENTITY_GATES.contains(&target) {
+            return Err(PadError::NotIdentityGate { op_type: target });
+        }
+        let layers = self.layers();
+        let qubits: Vec<ElementId> = self.qubits.iter().map(|q| q.id.clone()).collect();
+        let mut commands: Vec<Option<Command<P>>> = self.commands.drain(..).map(Some).collect();
+        for layer in &layers {
+            let mut idle: Vec<&ElementId> = qubits.iter().collect();
+            for command in layer.iter().filter_map(|&index| commands[index].take()) {
+                idle.retain(|q| !command.args.contains(q));
+                self.commands.push(command);
+            }
+            self.commands.extend(idle.into_iter().map(|qubit| Command {
+                op: Operation::from_optype(target),
+                args: vec![qubit.clone()],
+                opgroup: None,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Inserts an [`OpType::Reset`] at the start of every qubit whose first
+    /// operation is not already a reset.
+    ///
+    /// This makes the zero initialization of the qubits explicit, for
+    /// simulators that do not assume it. The resets are inserted in qubit
+    /// declaration order, and applying this twice has no further effect.
+    pub fn prepend_resets(&mut self) {
+        let resets: Vec<Command<P>> = self
+            .qubits
+            .iter()
+            .filter(|q| {
+                let first = self.commands.iter().find(|c| c.args.contains(&q.id));
+                !first.is_some_and(|c| c.op.op_type == OpType::Reset && c.op.conditional.is_none())
+            })
+            .map(|q| Command {
+                op: Operation::from_optype(OpType::Reset),
+                args: vec![q.id.clone()],
+                opgroup: None,
+            })
+            .collect();
+        self.commands.splice(0..0, resets);
+    }
+}
+
+impl<P> Operation<P> {
+    /// Returns whether the `i`-th argument of a command applying this
+    /// operation is a bit.
+    fn arg_is_bit(&self, i: usize) -> bool {
+        if let Some(signature) = &self.signature {
+            return signature.get(i).is_some_and(|s| s != "Q");
+        }
+        if let Some(conditional) = &self.conditional {
+            let width = conditional.width as usize;
+            return i < width || conditional.op.arg_is_bit(i - width);
+        }
+        if self.classical.is_some() || self.classical_expr.is_some() || self.wasm.is_some() {
+            return true;
+        }
+        self.op_type == OpType::Measure && i == 1
+    }
+}
+
+impl<P: PartialEq> SerialCircuit<P> {
+    /// Cancels adjacent pairs of mutually inverse commands, returning the
+    /// number of cancelled pairs.
+    ///
+    /// Two commands cancel when they apply unparametrized gates that are
+    /// each other's [dagger](OpType::dagger) to the same arguments in the same
+    /// order, and no command between them acts on any of those arguments.
+    /// Cancellations cascade, so `H X X H` is removed entirely.
+    ///
+    /// The rewrite is conservative: operations carrying any data beyond their
+    /// type (parameters, boxes, conditions, ...) and commands in an operation
+    /// group are never cancelled.
+    pub fn remove_adjacent_inverses(&mut self) -> usize {
+        let mut kept: Vec<Command<P>> = Vec::with_capacity(self.commands.len());
+        let mut cancelled = 0;
+        for command in self.commands.drain(..) {
+            let previous = kept
+                .iter()
+                .rposition(|c| c.args.iter().any(|arg| command.args.contains(arg)));
+            if let Some(index) = previous {
+                if kept[index].cancels_with(&command) {
+                    kept.remove(index);
+                    cancelled += 1;
+                    continue;
+                }
+            }
+            kept.push(command);
+        }
+        self.commands = kept;
+        cancelled
+    }
+}
+
+impl<P: PartialEq> Command<P> {
+    /// Returns `true` if this command followed by `other` is the identity.
+    ///
+    /// See [`SerialCircuit::remove_adjacent_inverses`].
+    fn cancels_with(&self, other: &Self) -> bool {
+        let is_plain =
+            |c: &Self| c.opgroup.is_none() && c.op == Operation::from_optype(c.op.op_type);
+        is_plain(self)
+            && is_plain(other)
+            && self.args == other.args
+            && self.op.op_type.dagger() == Some(other.op.op_type)
+    }
+}
+
+impl<P: Clone> SerialCircuit<P> {
+    /// Returns the circuit restricted to a subset of its qubits.
+    ///
+    /// Only the commands whose qubit arguments all lie in `qubits` are kept,
+    /// any other command is dropped. Bit arguments are not restricted, and all
+    /// the bit declarations are preserved.
+    ///
+    /// See [`SerialCircuit::strict_subcircuit`] for a variant that rejects
+    /// commands acting across the subset boundary.
+    pub fn subcircuit(&self, qubits: &[ElementId]) -> Self {
+        self.subcircuit_impl(qubits, false)
+            .expect("Non-strict subcircuit extraction cannot fail")
+    }
+
+    /// Returns the circuit restricted to a subset of its qubits.
+    ///
+    /// Commands acting only on qubits outside of `qubits` are dropped, but
+    /// commands acting on qubits both inside and outside of the subset
+    /// produce an error.
+    pub fn strict_subcircuit(&self, qubits: &[ElementId]) -> Result<Self, SubcircuitError> {
+        self.subcircuit_impl(qubits, true)
+    }
+
+    /// Splits the circuit into segments separated by measurements.
+    ///
+    /// Each segment ends with a run of consecutive [`OpType::Measure`]
+    /// commands, except possibly the last one, which holds the commands
+    /// following the final measurement. Every segment declares the same
+    /// registers as the original circuit. The global phase and the implicit
+    /// permutation are only kept in the last segment, so that composing
+    /// the segments in order yields the original circuit.
+    ///
+    /// Only top-level measurements are considered.
+    pub fn split_at_measurements(&self) -> Vec<Self>
+    where
+        P: From<&'static str>,
+    {
+        let mut segments: Vec<Vec<Command<P>>> = vec![Vec::new()];
+        let mut after_measure = false;
+        for command in &self.commands {
+            let is_measure = command.op.op_type == OpType::Measure;
+            if after_measure && !is_measure {
+                segments.push(Vec::new());
+            }
+            after_measure = is_measure;
+            segments.last_mut().unwrap().push(command.clone());
+        }
+
+        let n_segments = segments.len();
+        segments
+            .into_iter()
+            .enumerate()
+            .map(|(i, commands)| {
+                let mut segment = self.clone_with_commands(commands);
+                if i + 1 < n_segments {
+                    segment.phase = P::from("0");
+                    segment.implicit_permutation.clear();
+                }
+                segment
+            })
+            .collect()
+    }
+
+    fn subcircuit_impl(&self, qubits: &[ElementId], strict: bool) -> Result<Self, SubcircuitError> {
+        let declared = self.qubit_ids();
+        let selected: HashSet<&ElementId> = qubits.iter().collect();
+
+        let mut commands = Vec::new();
+        for (index, command) in self.commands.iter().enumerate() {
+            let mut inside = false;
+            let mut outside = None;
+            for arg in command.args.iter().filter(|arg| declared.contains(arg)) {
+                match selected.contains(arg) {
+                    true => inside = true,
+                    false => outside = Some(arg),
+                }
+            }
+            match outside {
+                None => commands.push(command.clone()),
+                Some(qubit) if strict && inside => {
+                    return Err(SubcircuitError::CommandCrossesBoundary {
+                        index,
+                        qubit: qubit.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let keep = |q: &ElementId| selected.contains(q);
+        let mut circuit = self.clone_with_commands(commands);
+        circuit.qubits.retain(|q| keep(&q.id));
+        circuit
+            .implicit_permutation
+            .retain(|perm| keep(&perm.0.id) && keep(&perm.1.id));
+        if let Some(created) = &mut circuit.created_qubits {
+            created.retain(|q| keep(&q.id));
+        }
+        if let Some(discarded) = &mut circuit.discarded_qubits {
+            discarded.retain(|q| keep(&q.id));
+        }
+        Ok(circuit)
+    }
+}
+
+impl SerialCircuit {
+    /// Replaces every command of type `target` with the commands of
+    /// `replacement`.
+    ///
+    /// The qubits and bits declared by the replacement circuit are mapped, in
+    /// order, onto the arguments of each replaced command. The global phase
+    /// of the replacement is added to the phase of the circuit once per
+    /// substitution. Only top-level commands are replaced; boxes and
+    /// conditional operations are left untouched.
+    ///
+    /// Returns an error, leaving the circuit unchanged, if any matching
+    /// command has a different number of arguments than the replacement or
+    /// has parameters, or if the replacement acts on units it does not
+    /// declare, such as ancillas.
+    pub fn replace_gate(
+        &mut self,
+        target: OpType,
+        replacement: &SerialCircuit,
+    ) -> Result<(), ReplaceError> {
+        let declared: HashSet<&ElementId> = replacement
+            .qubits
+            .iter()
+            .map(|q| &q.id)
+            .chain(replacement.bits.iter().map(|b| &b.id))
+            .collect();
+        if let Some(unit) = replacement
+            .commands
+            .iter()
+            .flat_map(|c| &c.args)
+            .find(|arg| !declared.contains(arg))
+        {
+            return Err(ReplaceError::UnmappedUnit { unit: unit.clone() });
+        }
+        let n_units = replacement.qubits.len() + replacement.bits.len();
+        for (index, command) in self.commands.iter().enumerate() {
+            if command.op.op_type != target {
+                continue;
+            }
+            if command.args.len() != n_units {
+                return Err(ReplaceError::ArityMismatch {
+                    index,
+                    expected: n_units,
+                    found: command.args.len(),
+                });
+            }
+            if !command.op.params().is_empty() {
+                return Err(ReplaceError::ParameterisedCommand { index });
+            }
+        }
+
+        let mut commands = Vec::with_capacity(self.commands.len());
+        for command in self.commands.drain(..) {
+            if command.op.op_type != target {
+                commands.push(command);
+                continue;
+            }
+            commands.extend(replacement.commands_on(&command.args));
+            if replacement.phase != "0" {
+                self.phase = add_angles(&self.phase, &replacement.phase);
+            }
+        }
+        self.commands = commands;
+        Ok(())
+    }
+
+    /// Fuses adjacent rotations about the same axis, returning the number of
+    /// merges.
+    ///
+    /// Two [`OpType::Rx`], [`OpType::Ry`] or [`OpType::Rz`] commands of the
+    /// same type on the same qubit, with no command between them acting on
+    /// that qubit, are replaced by a single rotation by the sum of their
+    /// angles. Numeric angles are added numerically, and any other pair of
+    /// expressions is combined symbolically as `a + b`.
+    ///
+    /// As with [`SerialCircuit::remove_adjacent_inverses`], operations
+    /// carrying any data beyond their type and angle, and commands in an
+    /// operation group, are never merged.
+    pub fn merge_single_qubit_rotations(&mut self) -> usize {
+        let mut kept: Vec<Command> = Vec::with_capacity(self.commands.len());
+        let mut merged = 0;
+        for command in self.commands.drain(..) {
+            let previous = kept
+                .iter()
+                .rposition(|c| c.args.iter().any(|arg| command.args.contains(arg)));
+            if let Some(index) = previous {
+                if let Some(angle) = kept[index].merged_rotation(&command) {
+                    kept[index].op.params = Some(vec![angle]);
+                    merged += 1;
+                    continue;
+                }
+            }
+            kept.push(command);
+        }
+        self.commands = kept;
+        merged
+    }
+
+    /// Removes the [`OpType::Phase`] commands, adding their angles to the
+    /// global phase of the circuit.
+    ///
+    /// Numeric angles are added numerically, and symbolic ones are combined
+    /// as `a + b`. Phases applied within boxes or conditional operations are
+    /// left unchanged.
+    pub fn remove_phase_ops(&mut self) {
+        let mut phase = self.phase.clone();
+        self.commands.retain(|command| {
+            if command.op.op_type != OpType::Phase {
+                return true;
+            }
+            if let [angle] = command.op.params() {
+                phase = add_angles(&phase, angle);
+            }
+            false
+        });
+        self.phase = phase;
+    }
+
+    /// Rewrites the circuit using only the CX, Rz and H gates.
+    ///
+    /// The Pauli, S, T, Rx and Ry gates, as well as CY, CZ, SWAP and CCX, are
+    /// replaced by fixed decompositions, and the global phase is adjusted so
+    /// that the circuit is unchanged. The decompositions are not optimised.
+    /// Identities are removed, while measurements, resets, barriers and
+    /// [`OpType::Phase`] commands are kept.
+    ///
+    /// Returns an error, leaving the circuit unchanged, if any other operation
+    /// is present, including boxes and conditional operations.
+    pub fn rebase_basic(&mut self) -> Result<(), RebaseError> {
+        let mut commands = Vec::with_capacity(self.commands.len());
+        let mut phase = 0.0;
+        for (index, command) in self.commands.iter().enumerate() {
+            let op_type = command.op.op_type;
+            if BASIC_OPERATIONS.contains(&op_type) {
+                commands.push(command.clone());
+                continue;
+            }
+            let unsupported = RebaseError::UnsupportedOperation { index, op_type };
+            let (gates, gates_phase) =
+                basic_decomposition(op_type, command.op.params()).ok_or(unsupported.clone())?;
+            for (gate, qubits, angle) in gates {
+                let args = qubits
+                    .iter()
+                    .map(|&i| command.args.get(i).cloned())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(unsupported.clone())?;
+                commands.push(Command {
+                    op: Operation {
+                        params: angle.map(|angle| vec![angle]),
+                        ..Operation::from_optype(gate)
+                    },
+                    args,
+                    opgroup: command.opgroup.clone(),
+                });
+            }
+            phase += gates_phase;
+        }
+        self.commands = commands;
+        if let Ok(phase) = param::format_number(phase.rem_euclid(2.0)) {
+            if phase != "0" {
+                self.phase = add_angles(&self.phase, &phase);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the inverse of the circuit.
+    ///
+    /// The commands are reversed and each one is replaced by its inverse: the
+    /// [dagger](OpType::dagger) of unparametrized gates, and the same gate
+    /// with [inverted parameters](OpType::inverse_params) for parametrized
+    /// ones. Boxes defined by a unitary matrix are replaced by their
+    /// [dagger](OpBox::dagger). Barriers are kept, and the global phase is
+    /// negated.
+    ///
+    /// Returns an error if the circuit has a non-trivial implicit
+    /// permutation, or if any command is not a plain invertible gate. In
+    /// particular, measurements, other boxes and conditional operations
+    /// cannot be inverted.
+    pub fn dagger(&self) -> Result<SerialCircuit, DaggerError> {
+        if self
+            .implicit_permutation
+            .iter()
+            .any(|ImplicitPermutation(from, to)| from != to)
+        {
+            return Err(DaggerError::ImplicitPermutation);
+        }
+        let mut commands = Vec::with_capacity(self.commands.len());
+        for (index, command) in self.commands.iter().enumerate().rev() {
+            let op_type = command.op.op_type;
+            let not_invertible = DaggerError::NotInvertible { index, op_type };
+            let op_box = match &command.op.op_box {
+                Some(op_box) => Some(op_box.dagger().ok_or_else(|| not_invertible.clone())?),
+                None => None,
+            };
+            let plain = Operation {
+                op_box: command.op.op_box.clone(),
+                params: command.op.params.clone(),
+                signature: command.op.signature.clone(),
+                data: command
+                    .op
+                    .data
+                    .clone()
+                    .filter(|_| op_type == OpType::Barrier),
+                ..Operation::from_optype(op_type)
+            };
+            if command.op != plain {
+                return Err(not_invertible);
+            }
+            let mut op = command.op.clone();
+            if op_type == OpType::Barrier {
+                // Barriers are their own inverse.
+            } else if op_box.is_some() {
+                op.op_box = op_box;
+            } else if let Some(dagger) = op_type.dagger().filter(|_| op.params.is_none()) {
+                op.op_type = dagger;
+            } else {
+                let params = op_type
+                    .inverse_params(command.op.params())
+                    .ok_or(not_invertible)?;
+                op.params = Some(params);
+            }
+            commands.push(Command {
+                op,
+                args: command.args.clone(),
+                opgroup: command.opgroup.clone(),
+            });
+        }
+
+        let mut dagger = self.clone_with_commands(commands);
+        dagger.phase = param::negate(&self.phase);
+        Ok(dagger)
+    }
+
+    /// Wraps the circuit between `compute` and its [dagger](Self::dagger).
+    ///
+    /// The commands of `compute` are prepended to the circuit, and those of
+    /// its inverse appended. They act on the units with the same names, so
+    /// every unit used by `compute` must be declared in the circuit. The
+    /// phases of `compute` and its inverse cancel out, so the global phase is
+    /// unchanged.
+    ///
+    /// Returns an error, leaving the circuit unchanged, if `compute` acts on
+    /// an undeclared unit or cannot be inverted.
+    pub fn conjugate_by(&mut self, compute: &SerialCircuit) -> Result<(), ConjugateError> {
+        let (qubits, bits) = (self.qubit_ids(), self.bit_ids());
+        let undeclared = compute
+            .commands
+            .iter()
+            .flat_map(|c| &c.args)
+            .find(|arg| !qubits.contains(arg) && !bits.contains(arg));
+        if let Some(unit) = undeclared {
+            return Err(ConjugateError::UndeclaredUnit { unit: unit.clone() });
+        }
+        let uncompute = compute.dagger().map_err(ConjugateError::Dagger)?;
+
+        let body = std::mem::take(&mut self.commands);
+        self.commands = compute
+            .commands
+            .iter()
+            .cloned()
+            .chain(body)
+            .chain(uncompute.commands)
+            .collect();
+        Ok(())
+    }
+
+    /// Inlines the circuits of the `CircBox` commands.
+    ///
+    /// Each command applying a [`OpBox::CircBox`] is replaced by the
+    /// commands of its circuit, whose qubits and bits are mapped in order
+    /// onto the command's arguments as in [`SerialCircuit::replace_gate`].
+    /// Boxes nested in the inlined circuits are inlined recursively, and the
+    /// global phase of each inlined circuit is added to the phase of the
+    /// circuit.
+    ///
+    /// The `CircBox`es within other boxes, such as the definition of a
+    /// [`OpBox::CustomGate`] or the operation of a [`OpBox::QControlBox`],
+    /// are inlined into the circuits of those boxes, which are kept.
+    ///
+    /// Commands whose operation type is in `excluded` are kept as they are,
+    /// including the circuits nested in them, so including
+    /// [`OpType::CircBox`] disables the inlining, and including e.g.
+    /// [`OpType::CustomGate`] leaves the gate definitions untouched. Boxes
+    /// whose circuit has a non-trivial implicit permutation, whose arguments
+    /// do not match the circuit's declared units, whose circuit acts on a
+    /// unit it does not declare, or that are nested in a conditional
+    /// operation are also kept.
+    pub fn inline_circboxes(&mut self, excluded: &HashSet<OpType>) {
+        let mut commands = Vec::with_capacity(self.commands.len());
+        for mut command in self.commands.drain(..) {
+            let inlinable = match command.inlinable_circuit() {
+                Some(circuit) if !excluded.contains(&OpType::CircBox) => Some(circuit.clone()),
+                _ => None,
+            };
+            let Some(mut circuit) = inlinable else {
+                command.op.inline_nested_circboxes(excluded);
+                commands.push(command);
+                continue;
+            };
+            circuit.inline_circboxes(excluded);
+            commands.extend(circuit.commands_on(&command.args));
+            if circuit.phase != "0" {
+                self.phase = add_angles(&self.phase, &circuit.phase);
+            }
+        }
+        self.commands = commands;
+    }
+
+    /// Returns the commands of the circuit, with its qubits and bits mapped
+    /// in declaration order onto `args`.
+    fn commands_on<'a>(&'a self, args: &'a [ElementId]) -> impl Iterator<Item = Command> + 'a {
+        let mapping: HashMap<&ElementId, &ElementId> = self
+            .qubits
+            .iter()
+            .map(|q| &q.id)
+            .chain(self.bits.iter().map(|b| &b.id))
+            .zip(args)
+            .collect();
+        self.commands.iter().map(move |c| Command {
+            op: c.op.clone(),
+            args: c
+                .args
+                .iter()
+                .map(|arg| mapping.get(arg).map_or(arg, |a| *a).clone())
+                .collect(),
+            opgroup: c.opgroup.clone(),
+        })
+    }
+}
+
+impl Command {
+    /// Returns the angle of the rotation equivalent to this command followed
+    /// by `other`, if both are mergeable rotations about the same axis.
+    ///
+    /// See [`SerialCircuit::merge_single_qubit_rotations`].
+    fn merged_rotation(&self, other: &Self) -> Option<String> {
+        let angle = |c: &Self| -> Option<String> {
+            let [angle] = c.op.params() else {
+                return None;
+            };
+            let plain = Operation {
+                params: c.op.params.clone(),
+                ..Operation::from_optype(c.op.op_type)
+            };
+            let is_rotation = matches!(c.op.op_type, OpType::Rx | OpType::Ry | OpType::Rz);
+            (is_rotation && c.opgroup.is_none() && c.op == plain && c.args.len() == 1)
+                .then(|| angle.clone())
+        };
+        if self.op.op_type != other.op.op_type || self.args != other.args {
+            return None;
+        }
+        Some(add_angles(&angle(self)?, &angle(other)?))
+    }
+
+    /// Returns the circuit of a `CircBox` command, if it can be inlined in
+    /// place of the command.
+    fn inlinable_circuit(&self) -> Option<&SerialCircuit> {
+        let Some(OpBox::CircBox { circuit, .. }) = &self.op.op_box else {
+            return None;
+        };
+        let permutes = circuit
+            .implicit_permutation
+            .iter()
+            .any(|ImplicitPermutation(from, to)| from != to);
+        let arity = circuit.qubits.len() + circuit.bits.len();
+        let (qubits, bits) = (circuit.qubit_ids(), circuit.bit_ids());
+        let declared = circuit
+            .commands
+            .iter()
+            .flat_map(|c| &c.args)
+            .all(|arg| qubits.contains(arg) || bits.contains(arg));
+        (self.op.op_type == OpType::CircBox && !permutes && self.args.len() == arity && declared)
+            .then_some(circuit)
+    }
+}
+
+impl Operation {
+    /// Inlines the `CircBox`es in the circuits nested in the box of the
+    /// operation, unless its type is in `excluded`.
+    ///
+    /// See [`SerialCircuit::inline_circboxes`].
+    fn inline_nested_circboxes(&mut self, excluded: &HashSet<OpType>) {
+        if excluded.contains(&self.op_type) {
+            return;
+        }
+        let Some(op_box) = &mut self.op_box else {
+            return;
+        };
+        for circuit in op_box.circuits_mut() {
+            circuit.inline_circboxes(excluded);
+        }
+        for op in op_box.operations_mut() {
+            op.inline_nested_circboxes(excluded);
+        }
+    }
+}
+
+/// Operations kept as they are by [`SerialCircuit::rebase_basic`].
+const BASIC_OPERATIONS: &[OpType] = &[
+    OpType::CX,
+    OpType::Rz,
+    OpType::H,
+    OpType::Measure,
+    OpType::Reset,
+    OpType::Barrier,
+    OpType::Phase,
+];
+
+/// A gate in a decomposition, as its type, the positions of its qubits in the
+/// arguments of the decomposed command, and its angle.
+type BasicGate = (OpType, &'static [usize], Option<String>);
+
+/// Returns the decomposition of a gate into CX, Rz and H gates, along with the
+/// global phase it introduces.
+fn basic_decomposition(op_type: OpType, params: &[String]) -> Option<(Vec<BasicGate>, f64)> {
+    const A: &[usize] = &[0];
+    const B: &[usize] = &[1];
+    const C: &[usize] = &[2];
+    let rz = |qubits: &'static [usize], angle: &str| (OpType::Rz, qubits, Some(angle.to_string()));
+    let h = |qubits: &'static [usize]| (OpType::H, qubits, None);
+    let cx = |qubits: &'static [usize]| (OpType::CX, qubits, None);
+    let decomposition = match (op_type, params) {
+        (OpType::noop, _) => (vec![], 0.0),
+        (OpType::Z, _) => (vec![rz(A, "1")], 0.5),
+        (OpType::S, _) => (vec![rz(A, "0.5")], 0.25),
+        (OpType::Sdg, _) => (vec![rz(A, "-0.5")], -0.25),
+        (OpType::T, _) => (vec![rz(A, "0.25")], 0.125),
+        (OpType::Tdg, _) => (vec![rz(A, "-0.25")], -0.125),
+        (OpType::X, _) => (vec![h(A), rz(A, "1"), h(A)], 0.5),
+        (OpType::Y, _) => (vec![rz(A, "1"), h(A), rz(A, "1"), h(A)], -0.5),
+        (OpType::Rx, [angle]) => (vec![h(A), rz(A, angle), h(A)], 0.0),
+        (OpType::Ry, [angle]) => (
+            vec![rz(A, "-0.5"), h(A), rz(A, angle), h(A), rz(A, "0.5")],
+            0.0,
+        ),
+        (OpType::CY, _) => (vec![rz(B, "-0.5"), cx(&[0, 1]), rz(B, "0.5")], 0.0),
+        (OpType::CZ, _) => (vec![h(B), cx(&[0, 1]), h(B)], 0.0),
+        (OpType::SWAP, _) => (vec![cx(&[0, 1]), cx(&[1, 0]), cx(&[0, 1])], 0.0),
+        (OpType::CCX, _) => (
+            vec![
+                h(C),
+                cx(&[1, 2]),
+                rz(C, "-0.25"),
+                cx(&[0, 2]),
+                rz(C, "0.25"),
+                cx(&[1, 2]),
+                rz(C, "-0.25"),
+                cx(&[0, 2]),
+                rz(B, "0.25"),
+                rz(C, "0.25"),
+                h(C),
+                cx(&[0, 1]),
+                rz(A, "0.25"),
+                rz(B, "-0.25"),
+                cx(&[0, 1]),
+            ],
+            0.125,
+        ),
+        _ => return None,
+    };
+    Some(decomposition)
+}
+
+/// Returns the sum of two angles, computed numerically if both are numbers
+/// and written as `a + b` otherwise.
+fn add_angles(a: &str, b: &str) -> String {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => param::format_number(x + y).unwrap_or_else(|_| format!("{a} + {b}")),
+        _ => format!("{a} + {b}"),
+    }
+}
@@ -0,0 +1,80 @@
+//! Structural validation of serialized circuits.
+
+use derive_more::{Display, Error};
+
+use super::SerialCircuit;
+
+/// Error returned by [`SerialCircuit::validate`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum CircuitError {
+    /// A conditional operation is controlled by a different number of bits
+    /// than its declared width.
+    #[display("conditional command {index} has width {width}, but only {n_control_bits} leading bit arguments")]
+    ConditionalWidthMismatch {
+        /// The index of the offending command.
+        index: usize,
+        /// The declared width of the condition.
+        width: u32,
+        /// The number of leading bit arguments of the command.
+        n_control_bits: usize,
+    },
+    /// A conditional operation compares its control bits against a value
+    /// that does not fit in its width.
+    #[display(
+        "conditional command {index} compares against {value}, which does not fit in {width} bits"
+    )]
+    ConditionalValueOutOfRange {
+        /// The index of the offending command.
+        index: usize,
+        /// The value the control bits are compared against.
+        value: u32,
+        /// The declared width of the condition.
+        width: u32,
+    },
+}
+
+impl<P> SerialCircuit<P> {
+    /// Checks the structural consistency of the circuit.
+    ///
+    /// The following properties are checked for each top-level command:
+    ///
+    /// - For conditional operations, the first `width` arguments are bits
+    ///   declared in the circuit, and the compared `value` is less than
+    ///   `2^width`.
+    ///
+    /// Returns the first error found.
+    pub fn validate(&self) -> Result<(), CircuitError> {
+        let bits = self.bit_ids();
+        for (index, command) in self.commands.iter().enumerate() {
+            let Some(conditional) = &command.op.conditional else {
+                continue;
+            };
+            let width = conditional.width;
+            let n_control_bits = command
+                .args
+                .iter()
+                .take(width as usize)
+                .take_while(|arg| bits.contains(arg))
+                .count();
+            if n_control_bits != width as usize {
+                return Err(CircuitError::ConditionalWidthMismatch {
+                    index,
+                    width,
+                    n_control_bits,
+                });
+            }
+            let fits = 1u32
+                .checked_shl(width)
+                .map_or(true, |limit| conditional.value < limit);
+            if !fits {
+                return Err(CircuitError::ConditionalValueOutOfRange {
+                    index,
+                    value: conditional.value,
+                    width,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+//! Gzip-compressed circuit JSON.
+//!
+//! The compressed payload is the plain JSON encoding of the circuit, so a
+//! decompressed file can be read with the regular [`serde_json`] functions.
+
+use std::io::{Read, Write};
+
+use derive_more::{Display, Error, From};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::circuit_json::SerialCircuit;
+
+/// Error returned when reading or writing a gzip-compressed circuit.
+#[derive(Debug, Display, Error, From)]
+#[non_exhaustive]
+pub enum CompressionError {
+    /// The compressed stream could not be read or written.
+    #[display("failed to process gzip stream: {_0}")]
+    Io(std::io::Error),
+    /// The decompressed payload is not a valid circuit.
+    #[display("invalid circuit JSON: {_0}")]
+    Json(serde_json::Error),
+}
+
+impl SerialCircuit {
+    /// Reads a circuit from gzip-compressed JSON.
+    pub fn from_gzip_json<R: Read>(reader: R) -> Result<Self, CompressionError> {
+        Ok(serde_json::from_reader(GzDecoder::new(reader))?)
+    }
+
+    /// Writes the circuit as gzip-compressed JSON.
+    pub fn to_gzip_json<W: Write>(&self, writer: W) -> Result<(), CompressionError> {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.finish()?;
+        Ok(())
+    }
+}
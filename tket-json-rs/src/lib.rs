@@ -2,14 +2,40 @@
 //! Serializable Rust definition for circuits and operations of the
 //! [TKET](https://github.com/Quantinuum/tket) quantum compiler.
 
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "cbor")]
+pub mod cbor;
 pub mod circuit_json;
 pub mod clexpr;
+#[cfg(feature = "compression")]
+pub mod compression;
 pub mod opbox;
 pub mod optype;
+pub mod param;
 pub mod pass;
 #[cfg(feature = "pyo3")]
 pub mod pytket;
+pub mod qiskit;
 pub mod register;
+#[cfg(feature = "proptest")]
+pub mod testing;
 
 pub use circuit_json::SerialCircuit;
 pub use optype::OpType;
+
+/// The TKET circuit schema version targeted by this crate.
+///
+/// See <https://github.com/CQCL/tket/blob/main/schemas/circuit_v1.json>.
+pub const SUPPORTED_CIRCUIT_SCHEMA: &str = "circuit_v1";
+
+/// The TKET compiler pass schema version targeted by this crate.
+///
+/// See <https://github.com/CQCL/tket/blob/main/schemas/compiler_pass_v1.json>.
+pub const SUPPORTED_PASS_SCHEMA: &str = "compiler_pass_v1";
+
+/// Returns whether `version` is one of the schema versions targeted by this
+/// crate, [`SUPPORTED_CIRCUIT_SCHEMA`] or [`SUPPORTED_PASS_SCHEMA`].
+pub fn supports_version(version: &str) -> bool {
+    [SUPPORTED_CIRCUIT_SCHEMA, SUPPORTED_PASS_SCHEMA].contains(&version)
+}
@@ -4,17 +4,18 @@
 //! [`circuit_v1`](https://github.com/Quantinuum/tket/blob/develop/schemas/circuit_v1.json)
 //! schema.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use crate::circuit_json::{
     ClassicalExp, CustomGate, Matrix, Operation, Permutation, SerialCircuit,
 };
 use crate::optype::OpType;
+use crate::param::{self, Param};
 use crate::register::{Bitstring, Qubit};
 #[cfg(feature = "schemars")]
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// Unique identifier for an [`OpBox`].
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
@@ -96,6 +97,7 @@ pub enum OpBox {
         /// List of Pauli operators.
         paulis: Vec<String>,
         /// Symengine expression.
+        #[serde(deserialize_with = "param::deserialize_param")]
         phase: String,
         /// Config param for decomposition of Pauli exponentials.
         #[serde(default)]
@@ -107,6 +109,7 @@ pub enum OpBox {
         /// List of List of Pauli operators.
         paulis_pair: Vec<Vec<String>>,
         /// List of Symengine expressions.
+        #[serde(deserialize_with = "param::deserialize_param_list")]
         phase_pair: Vec<String>,
         /// Config param for decomposition of Pauli exponentials.
         cx_config: String,
@@ -115,6 +118,7 @@ pub enum OpBox {
     PauliExpCommutingSetBox {
         id: BoxID,
         /// List of Symengine expressions.
+        #[serde(deserialize_with = "deserialize_pauli_gadgets")]
         pauli_gadgets: Vec<(Vec<String>, String)>,
         /// Config param for decomposition of Pauli exponentials.
         cx_config: String,
@@ -125,6 +129,7 @@ pub enum OpBox {
     TermSequenceBox {
         id: BoxID,
         /// List of Symengine expressions.
+        #[serde(deserialize_with = "deserialize_pauli_gadgets")]
         pauli_gadgets: Vec<(Vec<String>, String)>,
         /// Synthesis strategy. See [`PauliSynthStrat`].
         #[serde(default)]
@@ -150,9 +155,9 @@ pub enum OpBox {
         qubit_indices: Vec<(Qubit, u32)>,
         /// The phase polynomial definition.
         /// Represented by a map from bitstring to expression of coefficient.
-        phase_polynomial: Vec<Vec<(Bitstring, String)>>,
-        /// The additional linear transformation.
-        linear_transformation: Matrix,
+        phase_polynomial: Vec<(Bitstring, String)>,
+        /// The additional linear transformation, as a boolean matrix.
+        linear_transformation: Vec<Vec<bool>>,
     },
     /// A user-defined assertion specified by a list of Pauli stabilisers.
     StabiliserAssertionBox {
@@ -285,6 +290,154 @@ pub enum OpBox {
     },
 }
 
+impl OpBox {
+    /// Returns the inverse of a box defined by a unitary matrix.
+    ///
+    /// The inverse of a [`OpBox::Unitary1qBox`], [`OpBox::Unitary2qBox`] or
+    /// [`OpBox::Unitary3qBox`] is a box of the same kind holding the
+    /// conjugate transpose of the matrix, with a new [`BoxID`]. Returns
+    /// `None` for every other box.
+    pub fn dagger(&self) -> Option<OpBox> {
+        let dagger = match self {
+            OpBox::Unitary1qBox { matrix, .. } => OpBox::Unitary1qBox {
+                id: BoxID::new(),
+                matrix: conjugate_transpose(matrix),
+            },
+            OpBox::Unitary2qBox { matrix, .. } => OpBox::Unitary2qBox {
+                id: BoxID::new(),
+                matrix: conjugate_transpose(matrix),
+            },
+            OpBox::Unitary3qBox { matrix, .. } => OpBox::Unitary3qBox {
+                id: BoxID::new(),
+                matrix: Box::new(conjugate_transpose(matrix)),
+            },
+            _ => return None,
+        };
+        Some(dagger)
+    }
+
+    /// Returns the parameters held directly by the box.
+    ///
+    /// These are the phases of the Pauli exponential boxes, the coefficients
+    /// of a [`OpBox::PhasePolyBox`], and the parameters of a
+    /// [`OpBox::CustomGate`], but not the parameters of nested circuits or
+    /// operations.
+    pub fn params(&self) -> Vec<&String> {
+        match self {
+            OpBox::PauliExpBox { phase, .. } => vec![phase],
+            OpBox::PauliExpPairBox { phase_pair, .. } => phase_pair.iter().collect(),
+            OpBox::PauliExpCommutingSetBox { pauli_gadgets, .. }
+            | OpBox::TermSequenceBox { pauli_gadgets, .. } => {
+                pauli_gadgets.iter().map(|(_, phase)| phase).collect()
+            }
+            OpBox::PhasePolyBox {
+                phase_polynomial, ..
+            } => phase_polynomial.iter().map(|(_, coeff)| coeff).collect(),
+            OpBox::CustomGate { params, .. } => params.iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns mutable references to the parameters held directly by the box.
+    ///
+    /// See [`OpBox::params`].
+    pub fn params_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            OpBox::PauliExpBox { phase, .. } => vec![phase],
+            OpBox::PauliExpPairBox { phase_pair, .. } => phase_pair.iter_mut().collect(),
+            OpBox::PauliExpCommutingSetBox { pauli_gadgets, .. }
+            | OpBox::TermSequenceBox { pauli_gadgets, .. } => {
+                pauli_gadgets.iter_mut().map(|(_, phase)| phase).collect()
+            }
+            OpBox::PhasePolyBox {
+                phase_polynomial, ..
+            } => phase_polynomial
+                .iter_mut()
+                .map(|(_, coeff)| coeff)
+                .collect(),
+            OpBox::CustomGate { params, .. } => params.iter_mut().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the circuits directly nested in the box.
+    ///
+    /// This includes the circuit of a [`OpBox::CircBox`] and the definition of
+    /// a [`OpBox::CustomGate`], but not the circuits nested inside those.
+    pub fn circuits(&self) -> Vec<&SerialCircuit> {
+        match self {
+            OpBox::CircBox { circuit, .. } => vec![circuit],
+            OpBox::CustomGate { gate, .. } => vec![&gate.definition],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns mutable references to the circuits directly nested in the box.
+    ///
+    /// See [`OpBox::circuits`].
+    pub fn circuits_mut(&mut self) -> Vec<&mut SerialCircuit> {
+        match self {
+            OpBox::CircBox { circuit, .. } => vec![circuit],
+            OpBox::CustomGate { gate, .. } => vec![&mut gate.definition],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the operations directly nested in the box.
+    ///
+    /// This includes the controlled operation of a [`OpBox::QControlBox`], the
+    /// operations of a [`OpBox::ConjugationBox`], and the entries of the
+    /// multiplexor boxes' op maps.
+    pub fn operations(&self) -> Vec<&Operation> {
+        match self {
+            OpBox::QControlBox { op, .. } => vec![op],
+            OpBox::MultiplexorBox { op_map, .. }
+            | OpBox::MultiplexedRotationBox { op_map, .. }
+            | OpBox::MultiplexedU2Box { op_map, .. }
+            | OpBox::MultiplexedTensoredU2Box { op_map, .. } => {
+                op_map.iter().map(|(_, op)| op).collect()
+            }
+            OpBox::ConjugationBox {
+                compute,
+                action,
+                uncompute,
+                ..
+            } => [Some(compute), Some(action), uncompute.as_ref()]
+                .into_iter()
+                .flatten()
+                .map(|op| op.as_ref())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns mutable references to the operations directly nested in the box.
+    ///
+    /// See [`OpBox::operations`].
+    pub fn operations_mut(&mut self) -> Vec<&mut Operation> {
+        match self {
+            OpBox::QControlBox { op, .. } => vec![op],
+            OpBox::MultiplexorBox { op_map, .. }
+            | OpBox::MultiplexedRotationBox { op_map, .. }
+            | OpBox::MultiplexedU2Box { op_map, .. }
+            | OpBox::MultiplexedTensoredU2Box { op_map, .. } => {
+                op_map.iter_mut().map(|(_, op)| op).collect()
+            }
+            OpBox::ConjugationBox {
+                compute,
+                action,
+                uncompute,
+                ..
+            } => [Some(compute), Some(action), uncompute.as_mut()]
+                .into_iter()
+                .flatten()
+                .map(|op| op.as_mut())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 fn default_true() -> bool {
     true
 }
@@ -378,11 +531,11 @@ pub struct PauliStabiliser {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct ResourceData {
     /// Dictionary of counts of selected [`OpType`]s.
-    pub op_type_count: HashMap<OpType, ResourceBounds>,
+    pub op_type_count: BTreeMap<OpType, ResourceBounds>,
     /// Overall gate depth.
     pub gate_depth: ResourceBounds,
     /// Dictionary of depths of selected [`OpType`]s.
-    pub op_type_depth: HashMap<OpType, ResourceBounds>,
+    pub op_type_depth: BTreeMap<OpType, ResourceBounds>,
     /// Overall two-qubit-gate depth.
     pub two_qubit_gate_depth: ResourceBounds,
 }
@@ -408,6 +561,57 @@ pub struct UnitaryTableau {
     pub qubits: Vec<Qubit>,
 }
 
+impl UnitaryTableau {
+    /// Returns the X components of the images of the X operators on each
+    /// qubit.
+    ///
+    /// The tableau of an `n`-qubit unitary has `2n` rows: the images of the
+    /// X operators on each qubit, followed by the images of the Z operators.
+    /// The `xx`, `xz`, `zx` and `zz` blocks give the X and Z components of
+    /// each of those halves.
+    pub fn xx(&self) -> Vec<Vec<bool>> {
+        self.block(&self.tab.xmat, false)
+    }
+
+    /// Returns the Z components of the images of the X operators on each
+    /// qubit. See [`UnitaryTableau::xx`].
+    pub fn xz(&self) -> Vec<Vec<bool>> {
+        self.block(&self.tab.zmat, false)
+    }
+
+    /// Returns the X components of the images of the Z operators on each
+    /// qubit. See [`UnitaryTableau::xx`].
+    pub fn zx(&self) -> Vec<Vec<bool>> {
+        self.block(&self.tab.xmat, true)
+    }
+
+    /// Returns the Z components of the images of the Z operators on each
+    /// qubit. See [`UnitaryTableau::xx`].
+    pub fn zz(&self) -> Vec<Vec<bool>> {
+        self.block(&self.tab.zmat, true)
+    }
+
+    /// Returns the signs of the images of the X operators on each qubit, with
+    /// `true` denoting a negative sign.
+    pub fn x_phase(&self) -> Vec<bool> {
+        self.block(&self.tab.phase, false).concat()
+    }
+
+    /// Returns the signs of the images of the Z operators on each qubit, with
+    /// `true` denoting a negative sign.
+    pub fn z_phase(&self) -> Vec<bool> {
+        self.block(&self.tab.phase, true).concat()
+    }
+
+    /// Returns the rows of `matrix` corresponding to the images of the X or
+    /// Z operators.
+    fn block(&self, matrix: &[Vec<bool>], z_rows: bool) -> Vec<Vec<bool>> {
+        let n = self.qubits.len();
+        let start = if z_rows { n } else { 0 };
+        matrix.iter().skip(start).take(n).cloned().collect()
+    }
+}
+
 /// Binary matrix form of a collection of Pauli strings.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash)]
@@ -418,10 +622,32 @@ pub struct SymplecticTableau {
     /// Number of columns in the tableau.
     #[serde(default)]
     pub nqubits: u32,
-    /// The X matrix.
-    pub xmat: Matrix<bool>,
-    /// The Z matrix.
-    pub zmat: Matrix<bool>,
-    /// The phase matrix.
-    pub phase: Matrix<bool>,
+    /// The X components of the rows, one row per Pauli string.
+    pub xmat: Vec<Vec<bool>>,
+    /// The Z components of the rows, one row per Pauli string.
+    pub zmat: Vec<Vec<bool>>,
+    /// The sign of each row, as a column matrix. A `true` entry denotes a
+    /// negative sign.
+    pub phase: Vec<Vec<bool>>,
+}
+
+/// Returns the conjugate transpose of a square matrix of complex numbers.
+fn conjugate_transpose<const N: usize>(matrix: &[[(f64, f64); N]; N]) -> [[(f64, f64); N]; N] {
+    // Subtracting from zero avoids producing "-0" entries.
+    std::array::from_fn(|i| std::array::from_fn(|j| (matrix[j][i].0, 0.0 - matrix[j][i].1)))
+}
+
+/// Deserializes a list of Pauli gadgets, accepting phases given as numbers as
+/// in [`param::deserialize_param`].
+fn deserialize_pauli_gadgets<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Vec<String>, String)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let gadgets = Vec::<(Vec<String>, Param<String>)>::deserialize(deserializer)?;
+    Ok(gadgets
+        .into_iter()
+        .map(|(paulis, phase)| (paulis, phase.0))
+        .collect())
 }
@@ -6,7 +6,10 @@ use pyo3::prelude::*;
 #[cfg(feature = "schemars")]
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use strum::EnumString;
+use strum::{EnumString, VariantArray};
+
+mod opcode;
+mod qiskit;
 
 /// Operation types in a quantum circuit.
 #[cfg_attr(
@@ -22,8 +25,11 @@ use strum::EnumString;
     Default,
     PartialEq,
     Eq,
+    PartialOrd,
+    Ord,
     Hash,
     EnumString,
+    VariantArray,
     derive_more::Display,
 )]
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
@@ -616,3 +622,380 @@ pub enum OpType {
     /// \\cos\\frac{\\pi\\alpha}{2} \\end{array} \\right] \f$
     PhasedXX,
 }
+
+impl OpType {
+    /// Returns every operation type, in declaration order.
+    ///
+    /// Operation types carry no data, so this lists every variant. The
+    /// parameters of parametrized operations such as [`OpType::Rz`] are
+    /// stored separately in the [`Operation`] that uses them.
+    ///
+    ///   [`Operation`]: crate::circuit_json::Operation
+    pub fn all() -> &'static [OpType] {
+        Self::VARIANTS
+    }
+
+    /// Returns `true` if the operation is equal to its own inverse.
+    ///
+    /// Only unparametrized gates are considered. Parametrized gates such as
+    /// [`OpType::Rz`] may be self-inverse for specific parameter values, but
+    /// always return `false` here.
+    pub fn is_self_inverse(&self) -> bool {
+        matches!(
+            self,
+            OpType::H
+                | OpType::X
+                | OpType::Y
+                | OpType::Z
+                | OpType::CX
+                | OpType::CY
+                | OpType::CZ
+                | OpType::CCX
+                | OpType::SWAP
+                | OpType::CSWAP
+        )
+    }
+
+    /// Returns the operation type of the inverse of this operation, if it is
+    /// an unparametrized gate with a known inverse.
+    ///
+    /// Self-inverse gates (see [`OpType::is_self_inverse`]) are their own
+    /// dagger, and gates such as [`OpType::S`] and [`OpType::Sdg`] are
+    /// each other's dagger. Returns `None` for every other operation type,
+    /// including parametrized gates whose inverse depends on their
+    /// parameters.
+    pub fn dagger(&self) -> Option<OpType> {
+        if self.is_self_inverse() {
+            return Some(*self);
+        }
+        let dagger = match self {
+            OpType::S => OpType::Sdg,
+            OpType::Sdg => OpType::S,
+            OpType::T => OpType::Tdg,
+            OpType::Tdg => OpType::T,
+            OpType::V => OpType::Vdg,
+            OpType::Vdg => OpType::V,
+            OpType::SX => OpType::SXdg,
+            OpType::SXdg => OpType::SX,
+            OpType::CS => OpType::CSdg,
+            OpType::CSdg => OpType::CS,
+            OpType::CV => OpType::CVdg,
+            OpType::CVdg => OpType::CV,
+            OpType::CSX => OpType::CSXdg,
+            OpType::CSXdg => OpType::CSX,
+            _ => return None,
+        };
+        Some(dagger)
+    }
+
+    /// Returns the parameters of the inverse of a parametrized gate of this
+    /// type with the given parameters.
+    ///
+    /// The inverse is a gate of the same type. Most gates are inverted by
+    /// negating all their angles, while some also reorder them:
+    /// `TK1(a, b, c)` inverts to `TK1(-c, -b, -a)`, `U3(t, p, l)` and
+    /// `CU3(t, p, l)` to `U3(-t, -l, -p)` and `CU3(-t, -l, -p)`, and the
+    /// phase angle of [`OpType::PhasedX`], [`OpType::NPhasedX`] and
+    /// [`OpType::PhasedISWAP`] is kept. Returns `None` for unparametrized
+    /// operations (see [`OpType::dagger`] instead), for gates whose inverse
+    /// is not a gate of the same type with different parameters, and when
+    /// `params` has the wrong length.
+    pub fn inverse_params<P: AsRef<str>>(&self, params: &[P]) -> Option<Vec<String>> {
+        let neg = |i: usize| crate::param::negate(params[i].as_ref());
+        let keep = |i: usize| params[i].as_ref().to_string();
+        let inverse = match (self, params.len()) {
+            (
+                OpType::Phase
+                | OpType::Rx
+                | OpType::Ry
+                | OpType::Rz
+                | OpType::U1
+                | OpType::CRx
+                | OpType::CRy
+                | OpType::CRz
+                | OpType::CU1
+                | OpType::PhaseGadget
+                | OpType::ISWAP
+                | OpType::XXPhase
+                | OpType::YYPhase
+                | OpType::ZZPhase
+                | OpType::XXPhase3
+                | OpType::ESWAP
+                | OpType::CnRx
+                | OpType::CnRy
+                | OpType::CnRz,
+                1,
+            )
+            | (OpType::FSim, 2)
+            | (OpType::TK2, 3) => (0..params.len()).map(neg).collect(),
+            (OpType::PhasedX | OpType::NPhasedX, 2) => vec![neg(0), keep(1)],
+            (OpType::PhasedISWAP, 2) => vec![keep(0), neg(1)],
+            (OpType::TK1, 3) => vec![neg(2), neg(1), neg(0)],
+            (OpType::U3 | OpType::CU3, 3) => vec![neg(0), neg(2), neg(1)],
+            (OpType::GPI, 1) => vec![keep(0)],
+            _ => return None,
+        };
+        Some(inverse)
+    }
+
+    /// Returns the OpenQASM 2 keyword for operations of this type, if they
+    /// map directly onto an instruction of the language or of the standard
+    /// `qelib1.inc` library.
+    ///
+    /// Returns `None` for operations that must be decomposed first, such as
+    /// [`OpType::TK1`] or boxes. Note that parameters are given in half-turns
+    /// in TKET, and in radians in QASM.
+    pub fn qasm_name(&self) -> Option<&'static str> {
+        let name = match self {
+            OpType::noop => "id",
+            OpType::X => "x",
+            OpType::Y => "y",
+            OpType::Z => "z",
+            OpType::H => "h",
+            OpType::S => "s",
+            OpType::Sdg => "sdg",
+            OpType::T => "t",
+            OpType::Tdg => "tdg",
+            OpType::SX => "sx",
+            OpType::SXdg => "sxdg",
+            OpType::Rx => "rx",
+            OpType::Ry => "ry",
+            OpType::Rz => "rz",
+            OpType::U1 => "u1",
+            OpType::U2 => "u2",
+            OpType::U3 => "u3",
+            OpType::CX => "cx",
+            OpType::CY => "cy",
+            OpType::CZ => "cz",
+            OpType::CH => "ch",
+            OpType::CSX => "csx",
+            OpType::CRx => "crx",
+            OpType::CRy => "cry",
+            OpType::CRz => "crz",
+            OpType::CU1 => "cu1",
+            OpType::CU3 => "cu3",
+            OpType::CCX => "ccx",
+            OpType::SWAP => "swap",
+            OpType::CSWAP => "cswap",
+            OpType::XXPhase => "rxx",
+            OpType::ZZPhase => "rzz",
+            OpType::Measure => "measure",
+            OpType::Reset => "reset",
+            OpType::Barrier => "barrier",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// Returns `true` if operations of this type carry parameters.
+    ///
+    /// Parameters are stored in the
+    /// [`params`](crate::circuit_json::Operation#structfield.params) field of
+    /// the operation. The parameters of boxes, such as the phase of a
+    /// [`OpType::PauliExpBox`], are part of the box definition and not
+    /// considered here.
+    pub fn is_parametrized(&self) -> bool {
+        matches!(
+            self,
+            OpType::Phase
+                | OpType::Rx
+                | OpType::Ry
+                | OpType::Rz
+                | OpType::U3
+                | OpType::U2
+                | OpType::U1
+                | OpType::TK1
+                | OpType::TK2
+                | OpType::CRz
+                | OpType::CRx
+                | OpType::CRy
+                | OpType::CU1
+                | OpType::CU3
+                | OpType::PhaseGadget
+                | OpType::ISWAP
+                | OpType::PhasedX
+                | OpType::NPhasedX
+                | OpType::XXPhase
+                | OpType::YYPhase
+                | OpType::ZZPhase
+                | OpType::XXPhase3
+                | OpType::ESWAP
+                | OpType::FSim
+                | OpType::PhasedISWAP
+                | OpType::CnRx
+                | OpType::CnRy
+                | OpType::CnRz
+                | OpType::GPI
+                | OpType::GPI2
+                | OpType::AAMS
+                | OpType::TwinPhasedX
+                | OpType::PhasedXX
+        )
+    }
+
+    /// Returns `true` if a gate of this type with the given parameters is a
+    /// Clifford operation.
+    ///
+    /// Unparametrized gates such as [`OpType::H`], [`OpType::S`] and
+    /// [`OpType::CX`] are classified by type alone, as is the global
+    /// [`OpType::Phase`]. Rotations such as [`OpType::Rz`] or
+    /// [`OpType::ZZPhase`] are Clifford when all their angles are multiples
+    /// of 1/2, since parameters are given in half-turns. This is
+    /// conservative: symbolic parameters, non-unitary operations and boxes
+    /// return `false`.
+    pub fn is_clifford<P: AsRef<str>>(&self, params: &[P]) -> bool {
+        let is_clifford_angle = |param: &P| match param.as_ref().trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => {
+                let quarter_turns = 2.0 * value;
+                (quarter_turns - quarter_turns.round()).abs() < 1e-10
+            }
+            _ => false,
+        };
+        match self {
+            OpType::noop
+            | OpType::Phase
+            | OpType::H
+            | OpType::X
+            | OpType::Y
+            | OpType::Z
+            | OpType::S
+            | OpType::Sdg
+            | OpType::V
+            | OpType::Vdg
+            | OpType::SX
+            | OpType::SXdg
+            | OpType::CX
+            | OpType::CY
+            | OpType::CZ
+            | OpType::SWAP
+            | OpType::ZZMax
+            | OpType::ECR
+            | OpType::ISWAPMax => true,
+            OpType::Rx
+            | OpType::Ry
+            | OpType::Rz
+            | OpType::U1
+            | OpType::TK1
+            | OpType::TK2
+            | OpType::XXPhase
+            | OpType::YYPhase
+            | OpType::ZZPhase
+            | OpType::PhaseGadget => params.iter().all(is_clifford_angle),
+            _ => false,
+        }
+    }
+
+    /// Returns the number of control qubits of a controlled gate with a fixed
+    /// number of controls.
+    ///
+    /// For example, [`OpType::CX`] has one control and [`OpType::CCX`] has
+    /// two. Returns `None` for gates that are not controlled, and for
+    /// variadic controlled gates such as [`OpType::CnX`] or
+    /// [`OpType::QControlBox`], whose number of controls depends on the
+    /// command (see [`OpType::is_controlled`]).
+    pub fn n_controls(&self) -> Option<u32> {
+        match self {
+            OpType::CX
+            | OpType::CY
+            | OpType::CZ
+            | OpType::CH
+            | OpType::CV
+            | OpType::CVdg
+            | OpType::CSX
+            | OpType::CSXdg
+            | OpType::CS
+            | OpType::CSdg
+            | OpType::CRz
+            | OpType::CRx
+            | OpType::CRy
+            | OpType::CU1
+            | OpType::CU3
+            | OpType::CSWAP => Some(1),
+            OpType::CCX => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if operations of this type are controlled versions of
+    /// another operation, including the variadic controlled gates.
+    pub fn is_controlled(&self) -> bool {
+        self.n_controls().is_some()
+            || matches!(
+                self,
+                OpType::CnX
+                    | OpType::CnY
+                    | OpType::CnZ
+                    | OpType::CnRx
+                    | OpType::CnRy
+                    | OpType::CnRz
+                    | OpType::QControlBox
+            )
+    }
+
+    /// Returns `true` for the non-unitary operations that measure or
+    /// reinitialise a qubit: [`OpType::Measure`], [`OpType::Reset`] and
+    /// [`OpType::Collapse`].
+    ///
+    /// A circuit can be truncated after these operations when no other gate
+    /// follows them on their qubit.
+    pub fn is_terminal_classical(&self) -> bool {
+        matches!(self, OpType::Measure | OpType::Reset | OpType::Collapse)
+    }
+
+    /// Returns `true` if operations of `self` and `other` commute when
+    /// applied to overlapping qubits, for simple structural reasons.
+    ///
+    /// This is a conservative under-approximation: `true` guarantees that
+    /// the operations commute whatever their parameters and however their
+    /// qubits overlap, but `false` only means that commutation could not be
+    /// established. The cases recognised are:
+    ///
+    /// - [`OpType::noop`] and the global [`OpType::Phase`] commute with
+    ///   everything.
+    /// - Gates that are diagonal in the computational basis, such as
+    ///   [`OpType::Rz`] or [`OpType::CZ`], commute with each other.
+    /// - Single-qubit rotations about the X axis commute with each other, and
+    ///   similarly for the Y axis.
+    ///
+    /// Operations acting on disjoint qubits always commute, which is left to
+    /// the caller to check.
+    pub fn commutes_trivially_with(&self, other: &OpType) -> bool {
+        let is_identity = |op: &OpType| matches!(op, OpType::noop | OpType::Phase);
+        let is_x_axis = |op: &OpType| {
+            matches!(
+                op,
+                OpType::X | OpType::Rx | OpType::V | OpType::Vdg | OpType::SX | OpType::SXdg
+            )
+        };
+        let is_y_axis = |op: &OpType| matches!(op, OpType::Y | OpType::Ry);
+        is_identity(self)
+            || is_identity(other)
+            || (self.is_diagonal() && other.is_diagonal())
+            || (is_x_axis(self) && is_x_axis(other))
+            || (is_y_axis(self) && is_y_axis(other))
+    }
+
+    /// Returns `true` if the gate is diagonal in the computational basis.
+    fn is_diagonal(&self) -> bool {
+        matches!(
+            self,
+            OpType::Z
+                | OpType::S
+                | OpType::Sdg
+                | OpType::T
+                | OpType::Tdg
+                | OpType::Rz
+                | OpType::U1
+                | OpType::CZ
+                | OpType::CS
+                | OpType::CSdg
+                | OpType::CRz
+                | OpType::CU1
+                | OpType::CnZ
+                | OpType::CnRz
+                | OpType::ZZMax
+                | OpType::ZZPhase
+                | OpType::PhaseGadget
+        )
+    }
+}
@@ -0,0 +1,275 @@
+//! Stable numeric identifiers for operation types.
+
+use super::OpType;
+
+impl OpType {
+    /// Returns a stable numeric identifier for the operation type.
+    ///
+    /// Opcodes are assigned explicitly and never change once published:
+    /// existing operation types keep their code across releases, and new
+    /// variants are assigned the next unused code. They are suitable for
+    /// lookup tables and compact binary formats.
+    ///
+    /// See [`OpType::from_opcode`] for the inverse mapping.
+    pub fn opcode(&self) -> u16 {
+        match self {
+            OpType::Input => 0,
+            OpType::Output => 1,
+            OpType::Create => 2,
+            OpType::Discard => 3,
+            OpType::ClInput => 4,
+            OpType::ClOutput => 5,
+            OpType::Barrier => 6,
+            OpType::Label => 7,
+            OpType::Branch => 8,
+            OpType::Goto => 9,
+            OpType::Stop => 10,
+            OpType::ClassicalTransform => 11,
+            OpType::WASM => 12,
+            OpType::SetBits => 13,
+            OpType::CopyBits => 14,
+            OpType::RangePredicate => 15,
+            OpType::ExplicitPredicate => 16,
+            OpType::ExplicitModifier => 17,
+            OpType::MultiBit => 18,
+            OpType::Phase => 19,
+            OpType::Z => 20,
+            OpType::X => 21,
+            OpType::Y => 22,
+            OpType::S => 23,
+            OpType::Sdg => 24,
+            OpType::T => 25,
+            OpType::Tdg => 26,
+            OpType::V => 27,
+            OpType::Vdg => 28,
+            OpType::SX => 29,
+            OpType::SXdg => 30,
+            OpType::H => 31,
+            OpType::Rx => 32,
+            OpType::Ry => 33,
+            OpType::Rz => 34,
+            OpType::U3 => 35,
+            OpType::U2 => 36,
+            OpType::U1 => 37,
+            OpType::TK1 => 38,
+            OpType::TK2 => 39,
+            OpType::CX => 40,
+            OpType::CY => 41,
+            OpType::CZ => 42,
+            OpType::CH => 43,
+            OpType::CV => 44,
+            OpType::CVdg => 45,
+            OpType::CSX => 46,
+            OpType::CSXdg => 47,
+            OpType::CS => 48,
+            OpType::CSdg => 49,
+            OpType::CRz => 50,
+            OpType::CRx => 51,
+            OpType::CRy => 52,
+            OpType::CU1 => 53,
+            OpType::CU3 => 54,
+            OpType::PhaseGadget => 55,
+            OpType::CCX => 56,
+            OpType::SWAP => 57,
+            OpType::CSWAP => 58,
+            OpType::BRIDGE => 59,
+            OpType::noop => 60,
+            OpType::Measure => 61,
+            OpType::Collapse => 62,
+            OpType::Reset => 63,
+            OpType::ECR => 64,
+            OpType::ISWAP => 65,
+            OpType::PhasedX => 66,
+            OpType::NPhasedX => 67,
+            OpType::ZZMax => 68,
+            OpType::XXPhase => 69,
+            OpType::YYPhase => 70,
+            OpType::ZZPhase => 71,
+            OpType::XXPhase3 => 72,
+            OpType::ESWAP => 73,
+            OpType::FSim => 74,
+            OpType::Sycamore => 75,
+            OpType::ISWAPMax => 76,
+            OpType::PhasedISWAP => 77,
+            OpType::CnRx => 78,
+            OpType::CnRy => 79,
+            OpType::CnRz => 80,
+            OpType::CnX => 81,
+            OpType::CnY => 82,
+            OpType::CnZ => 83,
+            OpType::GPI => 84,
+            OpType::GPI2 => 85,
+            OpType::AAMS => 86,
+            OpType::CircBox => 87,
+            OpType::Unitary1qBox => 88,
+            OpType::Unitary2qBox => 89,
+            OpType::Unitary3qBox => 90,
+            OpType::ExpBox => 91,
+            OpType::PauliExpBox => 92,
+            OpType::PauliExpPairBox => 93,
+            OpType::PauliExpCommutingSetBox => 94,
+            OpType::TermSequenceBox => 95,
+            OpType::CliffBox => 96,
+            OpType::PhasePolyBox => 97,
+            OpType::Conditional => 98,
+            OpType::StabiliserAssertionBox => 99,
+            OpType::ProjectorAssertionBox => 100,
+            OpType::CustomGate => 101,
+            OpType::QControlBox => 102,
+            OpType::UnitaryTableauBox => 103,
+            OpType::ClassicalExpBox => 104,
+            OpType::MultiplexorBox => 105,
+            OpType::MultiplexedRotationBox => 106,
+            OpType::MultiplexedU2Box => 107,
+            OpType::MultiplexedTensoredU2Box => 108,
+            OpType::ToffoliBox => 109,
+            OpType::ConjugationBox => 110,
+            OpType::DummyBox => 111,
+            OpType::StatePreparationBox => 112,
+            OpType::DiagonalBox => 113,
+            OpType::ClExpr => 114,
+            OpType::RNGInput => 115,
+            OpType::RNGOutput => 116,
+            OpType::RNGSeed => 117,
+            OpType::RNGBound => 118,
+            OpType::RNGIndex => 119,
+            OpType::RNGNum => 120,
+            OpType::JobShotNum => 121,
+            OpType::TwinPhasedX => 122,
+            OpType::PhasedXX => 123,
+        }
+    }
+
+    /// Returns the operation type with the given [opcode](OpType::opcode),
+    /// or `None` if the code is not assigned.
+    pub fn from_opcode(opcode: u16) -> Option<OpType> {
+        let op = match opcode {
+            0 => OpType::Input,
+            1 => OpType::Output,
+            2 => OpType::Create,
+            3 => OpType::Discard,
+            4 => OpType::ClInput,
+            5 => OpType::ClOutput,
+            6 => OpType::Barrier,
+            7 => OpType::Label,
+            8 => OpType::Branch,
+            9 => OpType::Goto,
+            10 => OpType::Stop,
+            11 => OpType::ClassicalTransform,
+            12 => OpType::WASM,
+            13 => OpType::SetBits,
+            14 => OpType::CopyBits,
+            15 => OpType::RangePredicate,
+            16 => OpType::ExplicitPredicate,
+            17 => OpType::ExplicitModifier,
+            18 => OpType::MultiBit,
+            19 => OpType::Phase,
+            20 => OpType::Z,
+            21 => OpType::X,
+            22 => OpType::Y,
+            23 => OpType::S,
+            24 => OpType::Sdg,
+            25 => OpType::T,
+            26 => OpType::Tdg,
+            27 => OpType::V,
+            28 => OpType::Vdg,
+            29 => OpType::SX,
+            30 => OpType::SXdg,
+            31 => OpType::H,
+            32 => OpType::Rx,
+            33 => OpType::Ry,
+            34 => OpType::Rz,
+            35 => OpType::U3,
+            36 => OpType::U2,
+            37 => OpType::U1,
+            38 => OpType::TK1,
+            39 => OpType::TK2,
+            40 => OpType::CX,
+            41 => OpType::CY,
+            42 => OpType::CZ,
+            43 => OpType::CH,
+            44 => OpType::CV,
+            45 => OpType::CVdg,
+            46 => OpType::CSX,
+            47 => OpType::CSXdg,
+            48 => OpType::CS,
+            49 => OpType::CSdg,
+            50 => OpType::CRz,
+            51 => OpType::CRx,
+            52 => OpType::CRy,
+            53 => OpType::CU1,
+            54 => OpType::CU3,
+            55 => OpType::PhaseGadget,
+            56 => OpType::CCX,
+            57 => OpType::SWAP,
+            58 => OpType::CSWAP,
+            59 => OpType::BRIDGE,
+            60 => OpType::noop,
+            61 => OpType::Measure,
+            62 => OpType::Collapse,
+            63 => OpType::Reset,
+            64 => OpType::ECR,
+            65 => OpType::ISWAP,
+            66 => OpType::PhasedX,
+            67 => OpType::NPhasedX,
+            68 => OpType::ZZMax,
+            69 => OpType::XXPhase,
+            70 => OpType::YYPhase,
+            71 => OpType::ZZPhase,
+            72 => OpType::XXPhase3,
+            73 => OpType::ESWAP,
+            74 => OpType::FSim,
+            75 => OpType::Sycamore,
+            76 => OpType::ISWAPMax,
+            77 => OpType::PhasedISWAP,
+            78 => OpType::CnRx,
+            79 => OpType::CnRy,
+            80 => OpType::CnRz,
+            81 => OpType::CnX,
+            82 => OpType::CnY,
+            83 => OpType::CnZ,
+            84 => OpType::GPI,
+            85 => OpType::GPI2,
+            86 => OpType::AAMS,
+            87 => OpType::CircBox,
+            88 => OpType::Unitary1qBox,
+            89 => OpType::Unitary2qBox,
+            90 => OpType::Unitary3qBox,
+            91 => OpType::ExpBox,
+            92 => OpType::PauliExpBox,
+            93 => OpType::PauliExpPairBox,
+            94 => OpType::PauliExpCommutingSetBox,
+            95 => OpType::TermSequenceBox,
+            96 => OpType::CliffBox,
+            97 => OpType::PhasePolyBox,
+            98 => OpType::Conditional,
+            99 => OpType::StabiliserAssertionBox,
+            100 => OpType::ProjectorAssertionBox,
+            101 => OpType::CustomGate,
+            102 => OpType::QControlBox,
+            103 => OpType::UnitaryTableauBox,
+            104 => OpType::ClassicalExpBox,
+            105 => OpType::MultiplexorBox,
+            106 => OpType::MultiplexedRotationBox,
+            107 => OpType::MultiplexedU2Box,
+            108 => OpType::MultiplexedTensoredU2Box,
+            109 => OpType::ToffoliBox,
+            110 => OpType::ConjugationBox,
+            111 => OpType::DummyBox,
+            112 => OpType::StatePreparationBox,
+            113 => OpType::DiagonalBox,
+            114 => OpType::ClExpr,
+            115 => OpType::RNGInput,
+            116 => OpType::RNGOutput,
+            117 => OpType::RNGSeed,
+            118 => OpType::RNGBound,
+            119 => OpType::RNGIndex,
+            120 => OpType::RNGNum,
+            121 => OpType::JobShotNum,
+            122 => OpType::TwinPhasedX,
+            123 => OpType::PhasedXX,
+            _ => return None,
+        };
+        Some(op)
+    }
+}
@@ -0,0 +1,79 @@
+//! Conversion between operation types and Qiskit gate names.
+
+use super::OpType;
+
+/// Qiskit names for the operation types with a direct equivalent.
+///
+/// Qiskit expresses angles in radians, while TKET uses half-turns, so the
+/// parameters of rotations must be scaled when converting circuits.
+const QISKIT_NAMES: &[(OpType, &str)] = &[
+    (OpType::X, "x"),
+    (OpType::Y, "y"),
+    (OpType::Z, "z"),
+    (OpType::H, "h"),
+    (OpType::S, "s"),
+    (OpType::Sdg, "sdg"),
+    (OpType::T, "t"),
+    (OpType::Tdg, "tdg"),
+    (OpType::SX, "sx"),
+    (OpType::SXdg, "sxdg"),
+    (OpType::Rx, "rx"),
+    (OpType::Ry, "ry"),
+    (OpType::Rz, "rz"),
+    (OpType::U1, "p"),
+    (OpType::U2, "u2"),
+    (OpType::U3, "u"),
+    (OpType::CX, "cx"),
+    (OpType::CY, "cy"),
+    (OpType::CZ, "cz"),
+    (OpType::CH, "ch"),
+    (OpType::CS, "cs"),
+    (OpType::CSdg, "csdg"),
+    (OpType::CSX, "csx"),
+    (OpType::CRx, "crx"),
+    (OpType::CRy, "cry"),
+    (OpType::CRz, "crz"),
+    (OpType::CU1, "cp"),
+    (OpType::CU3, "cu3"),
+    (OpType::CCX, "ccx"),
+    (OpType::CnX, "mcx"),
+    (OpType::SWAP, "swap"),
+    (OpType::CSWAP, "cswap"),
+    (OpType::ISWAPMax, "iswap"),
+    (OpType::ECR, "ecr"),
+    (OpType::XXPhase, "rxx"),
+    (OpType::YYPhase, "ryy"),
+    (OpType::ZZPhase, "rzz"),
+    (OpType::noop, "id"),
+    (OpType::Measure, "measure"),
+    (OpType::Reset, "reset"),
+    (OpType::Barrier, "barrier"),
+];
+
+/// Legacy Qiskit names, accepted by [`OpType::from_qiskit_name`] only.
+const QISKIT_ALIASES: &[(OpType, &str)] =
+    &[(OpType::U1, "u1"), (OpType::U3, "u3"), (OpType::CU1, "cu1")];
+
+impl OpType {
+    /// Returns the name of the equivalent Qiskit standard gate, if any.
+    ///
+    /// Note that Qiskit expresses angles in radians rather than half-turns.
+    pub fn to_qiskit_name(&self) -> Option<&'static str> {
+        QISKIT_NAMES
+            .iter()
+            .find(|(op, _)| op == self)
+            .map(|(_, name)| *name)
+    }
+
+    /// Returns the operation type equivalent to a Qiskit standard gate, if
+    /// any.
+    ///
+    /// Legacy names such as `u3` are accepted as well.
+    pub fn from_qiskit_name(name: &str) -> Option<OpType> {
+        QISKIT_NAMES
+            .iter()
+            .chain(QISKIT_ALIASES)
+            .find(|(_, n)| *n == name)
+            .map(|(op, _)| *op)
+    }
+}
@@ -0,0 +1,260 @@
+//! Utilities for the symbolic parameter expressions used in circuits.
+//!
+//! Parameters are serialized as symengine expression strings, with angles
+//! expressed in half-turns.
+//! Expression strings are stored verbatim, so text such as `"pi/2"` or
+//! `"0.50"` roundtrips exactly. They are only parsed on demand, by helpers
+//! such as [`free_symbols`].
+//!
+//! Non-finite values are never valid parameters. They are rejected when
+//! converting a number to a parameter with [`format_number`], and when
+//! deserializing a parameter given either as a non-finite number or as a
+//! string that parses as one, such as `"inf"`, `"-Infinity"`, `"NaN"` or an
+//! overflowing literal like `"1e999"`. Symbolic expressions that merely
+//! evaluate to infinity, such as symengine's `oo`, are kept as they are.
+
+use std::fmt;
+
+use derive_more::{Display, Error};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde_json::Value;
+
+/// Named constants recognised by symengine, which are not free symbols.
+const CONSTANTS: &[&str] = &[
+    "pi",
+    "E",
+    "I",
+    "oo",
+    "zoo",
+    "nan",
+    "EulerGamma",
+    "Catalan",
+    "GoldenRatio",
+];
+
+/// Returns the free symbols in a parameter expression, in order of
+/// appearance.
+///
+/// Function names (identifiers followed by an opening parenthesis) and named
+/// constants such as `pi` are not considered symbols. Symbols appearing
+/// multiple times are repeated.
+pub fn free_symbols(expr: &str) -> Vec<&str> {
+    let mut symbols = Vec::new();
+    let mut chars = expr.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            // Skip numeric literals, including their exponent.
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' {
+                    chars.next();
+                } else if c == 'e' || c == 'E' {
+                    chars.next();
+                    if let Some(&(_, '+' | '-')) = chars.peek() {
+                        chars.next();
+                    }
+                } else {
+                    break;
+                }
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let ident = &expr[start..end];
+            let is_call = expr[end..].trim_start().starts_with('(');
+            if !is_call && !CONSTANTS.contains(&ident) {
+                symbols.push(ident);
+            }
+        }
+    }
+    symbols
+}
+
+/// Error returned when a parameter would hold a non-finite value.
+#[derive(Clone, Copy, Debug, PartialEq, Display, Error)]
+#[display("non-finite parameter value {value}")]
+pub struct NonFiniteError {
+    /// The offending value.
+    pub value: f64,
+}
+
+/// Formats a number as a parameter expression.
+///
+/// Returns an error for infinities and NaN, which cannot be represented.
+pub fn format_number(value: f64) -> Result<String, NonFiniteError> {
+    if value.is_finite() {
+        Ok(value.to_string())
+    } else {
+        Err(NonFiniteError { value })
+    }
+}
+
+/// Returns the negation of a parameter expression.
+///
+/// Numeric expressions are negated numerically, and symbolic ones are
+/// wrapped as `-(expr)`.
+pub fn negate(expr: &str) -> String {
+    match expr.trim().parse::<f64>() {
+        // Subtracting from zero avoids producing "-0".
+        Ok(value) if value.is_finite() => (0.0 - value).to_string(),
+        _ => format!("-({expr})"),
+    }
+}
+
+/// Returns an error if `expr` is a literal for a non-finite number.
+fn check_finite_literal<E: de::Error>(expr: &str) -> Result<(), E> {
+    match expr.trim().parse::<f64>() {
+        Ok(value) if !value.is_finite() => Err(E::custom(NonFiniteError { value })),
+        _ => Ok(()),
+    }
+}
+
+/// Deserializes a parameter given either as an expression string or as a bare
+/// JSON number.
+///
+/// Parameter types that accept numbers, such as `f64`, receive them as they
+/// are. Other types receive the decimal string representation of numbers, so
+/// `0.5` and `"0.5"` decode identically into a `String`. Non-finite values are
+/// rejected, see the [module documentation](self).
+pub(crate) fn deserialize_param<'de, D, P>(deserializer: D) -> Result<P, D::Error>
+where
+    D: Deserializer<'de>,
+    P: Deserialize<'de>,
+{
+    let value = Value::deserialize(FiniteNumbers(deserializer))?;
+    param_from_value(value).map_err(de::Error::custom)
+}
+
+/// Deserializes an optional list of parameters, see [`deserialize_param`].
+pub(crate) fn deserialize_params<'de, D, P>(deserializer: D) -> Result<Option<Vec<P>>, D::Error>
+where
+    D: Deserializer<'de>,
+    P: Deserialize<'de>,
+{
+    let params = Option::<Vec<Param<P>>>::deserialize(deserializer)?;
+    Ok(params.map(|params| params.into_iter().map(|p| p.0).collect()))
+}
+
+/// Deserializes a list of parameters, see [`deserialize_param`].
+pub(crate) fn deserialize_param_list<'de, D, P>(deserializer: D) -> Result<Vec<P>, D::Error>
+where
+    D: Deserializer<'de>,
+    P: Deserialize<'de>,
+{
+    let params = Vec::<Param<P>>::deserialize(deserializer)?;
+    Ok(params.into_iter().map(|p| p.0).collect())
+}
+
+/// A parameter deserialized with [`deserialize_param`], for use in nested
+/// structures.
+pub(crate) struct Param<P>(pub(crate) P);
+
+impl<'de, P: Deserialize<'de>> Deserialize<'de> for Param<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_param(deserializer).map(Param)
+    }
+}
+
+/// Converts a buffered parameter value into the parameter type, presenting
+/// numbers as strings to types that do not accept numbers.
+fn param_from_value<'de, P: Deserialize<'de>>(value: Value) -> serde_json::Result<P> {
+    match value {
+        Value::Number(number) => {
+            let string = match number.as_f64() {
+                Some(v) if !(number.is_i64() || number.is_u64()) => {
+                    format_number(v).map_err(de::Error::custom)?
+                }
+                _ => number.to_string(),
+            };
+            P::deserialize(Value::Number(number))
+                .or_else(|e| P::deserialize(Value::String(string)).map_err(|_| e))
+        }
+        value => P::deserialize(value),
+    }
+}
+
+/// A deserializer adaptor rejecting non-finite numbers and number literals.
+struct FiniteNumbers<D>(D);
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for FiniteNumbers<D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_any(FiniteNumbersVisitor(visitor))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// A visitor adaptor for [`FiniteNumbers`], forwarding every value to the
+/// wrapped visitor after checking that it is finite.
+struct FiniteNumbersVisitor<V>(V);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for FiniteNumbersVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.0.visit_i64(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.0.visit_u64(v)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        format_number(v).map_err(E::custom)?;
+        self.0.visit_f64(v)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        self.0.visit_bool(v)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        check_finite_literal(v)?;
+        self.0.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        check_finite_literal(v)?;
+        self.0.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        check_finite_literal(&v)?;
+        self.0.visit_string(v)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_some(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(seq)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_map(map)
+    }
+}
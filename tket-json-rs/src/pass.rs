@@ -3,32 +3,26 @@
 //! Based on the `compiler_pass_v1` schema.
 //! <https://github.com/CQCL/tket/blob/main/schemas/compiler_pass_v1.json>
 
+pub mod architecture;
+mod compact;
+mod explain;
+mod numeric;
+pub mod placement;
+pub mod predicate;
+pub mod registry;
 pub mod standard;
 
 #[cfg(feature = "schemars")]
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::SerialCircuit;
+pub use architecture::Architecture;
+pub use explain::explain_error;
+pub use placement::Placement;
+pub use predicate::Predicate;
 use standard::StandardPass;
 
-/// Stub for a serialized architecture blob following `architecture_v1.json`.
-//
-// TODO: Replace with the actual schema.
-// <https://github.com/CQCL/tket/blob/main/schemas/architecture_v1.json>
-pub type Architecture = serde_json::Value;
-
-/// Stub for a serialized placement blob following `placement_v1.json`.
-//
-// TODO: Replace with the actual schema.
-// <https://github.com/CQCL/tket/blob/main/schemas/placement_v1.json>
-pub type Placement = serde_json::Value;
-
-/// Stub for a serialized predicate blob following `predicate_v1.json`.
-//
-// TODO: Replace with the actual schema.
-// <https://github.com/CQCL/tket/blob/main/schemas/predicate_v1.json>
-pub type Predicate = serde_json::Value;
-
 /// A pass in a TKET circuit.
 //
 // This struct is both tagged adjacently (with a `pass_class` string field) and
@@ -69,6 +63,153 @@ pub enum BasePass {
     },
 }
 
+impl BasePass {
+    /// Returns the circuits embedded in this pass and any nested pass.
+    ///
+    /// See [`StandardPass::circuits`].
+    pub fn circuits(&self) -> Vec<&SerialCircuit> {
+        match self {
+            BasePass::StandardPass { pass } => pass.circuits(),
+            BasePass::SequencePass { pass } => {
+                pass.passes().iter().flat_map(BasePass::circuits).collect()
+            }
+            BasePass::RepeatPass { pass } => pass.body().circuits(),
+            BasePass::RepeatUntilSatisfiedPass { pass } => pass.body().circuits(),
+        }
+    }
+
+    /// Returns the pass nested at `path`.
+    ///
+    /// Each element of the path selects a child of the current pass: the
+    /// pass at that position in a [`SequencePass`], or the body of a
+    /// [`RepeatPass`] or [`RepeatUntilSatisfiedPass`] for the index `0`. An
+    /// empty path selects the pass itself. Returns `None` if the path does
+    /// not lead to a pass.
+    pub fn get_at(&self, path: &[usize]) -> Option<&BasePass> {
+        let Some((&index, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let child = match self {
+            BasePass::StandardPass { .. } => None,
+            BasePass::SequencePass { pass } => pass.sequence.get(index),
+            BasePass::RepeatPass { pass } => (index == 0).then_some(&*pass.body),
+            BasePass::RepeatUntilSatisfiedPass { pass } => (index == 0).then_some(&*pass.body),
+        };
+        child?.get_at(rest)
+    }
+
+    /// Returns a mutable reference to the pass nested at `path`.
+    ///
+    /// See [`BasePass::get_at`].
+    pub fn get_at_mut(&mut self, path: &[usize]) -> Option<&mut BasePass> {
+        let Some((&index, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let child = match self {
+            BasePass::StandardPass { .. } => None,
+            BasePass::SequencePass { pass } => pass.sequence.get_mut(index),
+            BasePass::RepeatPass { pass } => (index == 0).then_some(&mut *pass.body),
+            BasePass::RepeatUntilSatisfiedPass { pass } => (index == 0).then_some(&mut *pass.body),
+        };
+        child?.get_at_mut(rest)
+    }
+
+    /// Replaces the architecture of every pass in the tree that targets one.
+    ///
+    /// Returns the number of passes that were updated. See
+    /// [`StandardPass::set_architecture`] for the passes affected. The
+    /// architectures of the predicates of [`RepeatUntilSatisfiedPass`]es are
+    /// left unchanged.
+    pub fn set_architecture(&mut self, arch: &Architecture) -> usize {
+        match self {
+            BasePass::StandardPass { pass } => usize::from(pass.set_architecture(arch)),
+            BasePass::SequencePass { pass } => pass
+                .sequence
+                .iter_mut()
+                .map(|p| p.set_architecture(arch))
+                .sum(),
+            BasePass::RepeatPass { pass } => pass.body.set_architecture(arch),
+            BasePass::RepeatUntilSatisfiedPass { pass } => pass.body.set_architecture(arch),
+        }
+    }
+
+    /// Simplifies the pass without changing its effect.
+    ///
+    /// Within every sequence, a standard pass immediately following an
+    /// identical pass is removed if it is [idempotent](StandardPass::is_idempotent).
+    /// Sequences left with a single pass are then replaced by that pass.
+    /// Nested passes are simplified recursively.
+    pub fn simplify(self) -> BasePass {
+        match self {
+            BasePass::StandardPass { .. } => self,
+            BasePass::SequencePass { pass } => {
+                let mut sequence: Vec<BasePass> = Vec::with_capacity(pass.sequence.len());
+                for p in pass.sequence.into_iter().map(BasePass::simplify) {
+                    let duplicate = match (sequence.last(), &p) {
+                        (
+                            Some(BasePass::StandardPass { pass: previous }),
+                            BasePass::StandardPass { pass: current },
+                        ) => previous == current && current.is_idempotent(),
+                        _ => false,
+                    };
+                    if !duplicate {
+                        sequence.push(p);
+                    }
+                }
+                if sequence.len() == 1 {
+                    return sequence.pop().unwrap();
+                }
+                BasePass::SequencePass {
+                    pass: SequencePass { sequence },
+                }
+            }
+            BasePass::RepeatPass { mut pass } => {
+                *pass.body = pass.body.simplify();
+                BasePass::RepeatPass { pass }
+            }
+            BasePass::RepeatUntilSatisfiedPass { mut pass } => {
+                *pass.body = pass.body.simplify();
+                BasePass::RepeatUntilSatisfiedPass { pass }
+            }
+        }
+    }
+
+    /// Serializes the pass, omitting the fields that hold their default value.
+    ///
+    /// Some pytket releases omit these fields, so this produces output closer
+    /// to theirs. Only fields that are filled in when missing are omitted, so
+    /// the output still deserializes to the same pass. Use the regular
+    /// [`Serialize`] implementation for an exact encoding.
+    pub fn to_compact_json(&self) -> serde_json::Result<serde_json::Value> {
+        compact::to_compact_value(self)
+    }
+
+    /// Serializes the pass as a JSON string accepted by pytket's
+    /// `BasePass.from_dict`.
+    ///
+    /// This is the regular [`Serialize`] encoding, which writes every field
+    /// pytket requires, including those holding their default value. Use
+    /// [`BasePass::to_compact_json`] to omit the defaulted fields instead.
+    pub fn to_pytket_dict_json(&self) -> String {
+        // Pass definitions only contain maps with string keys, so
+        // serialization cannot fail.
+        serde_json::to_string(self).expect("pass definitions serialize to JSON")
+    }
+}
+
+/// A circuit bundled with the sequence of passes applied to it.
+///
+/// This matches the artifacts exported by pytket compilation units and
+/// backends, which record the compiled circuit alongside its pass history.
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CompilationRecord {
+    /// The compiled circuit.
+    pub circuit: SerialCircuit,
+    /// The passes applied to the circuit, in order.
+    pub passes: Vec<BasePass>,
+}
+
 /// A pass that executes a sequence of passes in order.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -77,6 +218,28 @@ pub struct SequencePass {
     pub sequence: Vec<BasePass>,
 }
 
+impl SequencePass {
+    /// Returns the passes to be executed, in order.
+    ///
+    /// ```
+    /// use tket_json_rs::pass::BasePass;
+    ///
+    /// let pass: BasePass = serde_json::from_str(r#"{
+    ///     "pass_class": "SequencePass",
+    ///     "SequencePass": {"sequence": [
+    ///         {"pass_class": "StandardPass", "StandardPass": {"name": "RemoveBarriers"}},
+    ///         {"pass_class": "StandardPass", "StandardPass": {"name": "SquashTK1"}}
+    ///     ]}
+    /// }"#).unwrap();
+    ///
+    /// let BasePass::SequencePass { pass } = &pass else { unreachable!() };
+    /// assert_eq!(pass.passes().len(), 2);
+    /// ```
+    pub fn passes(&self) -> &[BasePass] {
+        &self.sequence
+    }
+}
+
 /// A pass that iterates an internal pass until no further change.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -85,6 +248,32 @@ pub struct RepeatPass {
     pub body: Box<BasePass>,
 }
 
+impl RepeatPass {
+    /// Returns the body of the loop.
+    ///
+    /// ```
+    /// use tket_json_rs::pass::standard::StandardPass;
+    /// use tket_json_rs::pass::BasePass;
+    ///
+    /// let pass: BasePass = serde_json::from_str(r#"{
+    ///     "pass_class": "RepeatPass",
+    ///     "RepeatPass": {"body": {
+    ///         "pass_class": "StandardPass",
+    ///         "StandardPass": {"name": "RemoveRedundancies"}
+    ///     }}
+    /// }"#).unwrap();
+    ///
+    /// let BasePass::RepeatPass { pass } = &pass else { unreachable!() };
+    /// assert!(matches!(
+    ///     pass.body(),
+    ///     BasePass::StandardPass { pass: StandardPass::RemoveRedundancies }
+    /// ));
+    /// ```
+    pub fn body(&self) -> &BasePass {
+        &self.body
+    }
+}
+
 /// A pass that iterates an internal pass until some predicate is satisfied.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -95,3 +284,24 @@ pub struct RepeatUntilSatisfiedPass {
     /// The loop is terminated when this predicate returns True.
     pub predicate: Predicate,
 }
+
+impl RepeatUntilSatisfiedPass {
+    /// Returns the body of the loop.
+    pub fn body(&self) -> &BasePass {
+        &self.body
+    }
+
+    /// Returns the predicate that terminates the loop.
+    pub fn predicate(&self) -> &Predicate {
+        &self.predicate
+    }
+}
+
+/// Returns the JSON schema of a serialized [`BasePass`].
+///
+/// The schema includes the definitions of every nested type, such as
+/// [`Architecture`], [`Placement`], and [`Predicate`].
+#[cfg(feature = "schemars")]
+pub fn export_schema() -> schemars::Schema {
+    schemars::schema_for!(BasePass)
+}
@@ -0,0 +1,75 @@
+//! Serialized definition for TKET architectures.
+//!
+//! Based on the `architecture_v1` schema.
+//! <https://github.com/CQCL/tket/blob/main/schemas/architecture_v1.json>
+
+use derive_more::{Display, Error};
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::register::ElementId;
+use crate::SerialCircuit;
+
+/// The connectivity graph of a device.
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Architecture {
+    /// The nodes of the architecture.
+    pub nodes: Vec<ElementId>,
+    /// The weighted edges between the nodes.
+    #[serde(default)]
+    pub links: Vec<ArchitectureLink>,
+    /// Error rates of single-qubit operations on each node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_errors: Option<Vec<(ElementId, f64)>>,
+    /// Error rates of two-qubit operations on each pair of connected nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_errors: Option<Vec<((ElementId, ElementId), f64)>>,
+    /// Measurement error rates of each node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readout_errors: Option<Vec<(ElementId, f64)>>,
+}
+
+/// A weighted edge between two nodes of an [`Architecture`].
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ArchitectureLink {
+    /// The pair of connected nodes.
+    pub link: (ElementId, ElementId),
+    /// The weight of the edge.
+    #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+    pub weight: u32,
+}
+
+/// Error returned when a circuit cannot be mapped onto an [`Architecture`].
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum MappingError {
+    /// The architecture has fewer nodes than the circuit has qubits.
+    #[display("the architecture has {n_nodes} nodes, but the circuit has {n_qubits} qubits")]
+    NotEnoughNodes {
+        /// Number of nodes in the architecture.
+        n_nodes: usize,
+        /// Number of qubits in the circuit.
+        n_qubits: usize,
+    },
+}
+
+impl Architecture {
+    /// Returns the number of nodes in the architecture.
+    pub fn n_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks that the architecture has enough nodes to hold every qubit of
+    /// the circuit.
+    pub fn check_against<P>(&self, circuit: &SerialCircuit<P>) -> Result<(), MappingError> {
+        let n_nodes = self.n_nodes();
+        let n_qubits = circuit.qubits.len();
+        if n_nodes < n_qubits {
+            return Err(MappingError::NotEnoughNodes { n_nodes, n_qubits });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+//! Compact serialization of pass definitions.
+//!
+//! Fields that are filled in when missing consult a flag through
+//! [`skip_default`], which is only set while [`to_compact_value`] runs. The
+//! regular [`Serialize`] implementation is unaffected.
+
+use std::cell::Cell;
+
+use serde::Serialize;
+
+thread_local! {
+    /// Whether defaulted fields holding their default value are omitted.
+    static COMPACT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Restores the previous value of [`COMPACT`] when dropped, even on panic.
+struct CompactGuard(bool);
+
+impl Drop for CompactGuard {
+    fn drop(&mut self) {
+        COMPACT.with(|compact| compact.set(self.0));
+    }
+}
+
+/// Serializes `value`, omitting the defaulted fields that hold their default
+/// value.
+pub(crate) fn to_compact_value<T: Serialize>(value: &T) -> serde_json::Result<serde_json::Value> {
+    let _guard = CompactGuard(COMPACT.with(|compact| compact.replace(true)));
+    serde_json::to_value(value)
+}
+
+/// Returns `true` if a defaulted field should be omitted, i.e. during compact
+/// serialization when it holds its default value.
+///
+/// Use as `#[serde(default, skip_serializing_if = "...")]`.
+pub(crate) fn skip_default<T: Default + PartialEq>(value: &T) -> bool {
+    COMPACT.with(Cell::get) && *value == T::default()
+}
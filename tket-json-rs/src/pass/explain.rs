@@ -0,0 +1,116 @@
+//! Error reports for pass definitions that fail to deserialize.
+//!
+//! The errors returned by `serde` for the internally tagged pass types do not
+//! say where in the nested definition the failure occurred. The report is
+//! built by deserializing the nested passes one at a time, down to the
+//! innermost one that fails, and then checking which of its fields is
+//! responsible.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{BasePass, Predicate, StandardPass};
+
+/// Returns a description of why `json` is not a valid [`BasePass`], with the
+/// path to the offending value.
+///
+/// The path is written in the JSONPath style, e.g.
+/// `$.SequencePass.sequence[1].StandardPass.allow_swaps`, and is followed by
+/// the `serde` error message, which names the expected type. Returns an empty
+/// string if `json` deserializes to a [`BasePass`].
+///
+/// ```
+/// use tket_json_rs::pass::explain_error;
+///
+/// let json = r#"{"pass_class": "StandardPass", "StandardPass": {"name": "CliffordSimp", "allow_swaps": "yes"}}"#;
+/// assert!(explain_error(json).starts_with("$.StandardPass.allow_swaps: invalid type"));
+/// ```
+pub fn explain_error(json: &str) -> String {
+    let value: Value = match serde_json::from_str(json) {
+        Ok(value) => value,
+        Err(e) => return format!("invalid JSON: {e}"),
+    };
+    match locate_pass(&value, "$".to_string()) {
+        Some((path, e)) => format!("{path}: {e}"),
+        None => String::new(),
+    }
+}
+
+/// Returns the path to the innermost nested pass of `value` that fails to
+/// deserialize, refined to one of its fields, along with its error.
+fn locate_pass(value: &Value, path: String) -> Option<(String, serde_json::Error)> {
+    let e = BasePass::deserialize(value).err()?;
+    let class = value.get("pass_class").and_then(Value::as_str);
+    let definition = class.and_then(|class| Some((class, value.get(class)?)));
+    let Some((class, definition)) = definition else {
+        return Some((path, e));
+    };
+    let path = format!("{path}.{class}");
+    match class {
+        "StandardPass" => {
+            if let Err(e) = StandardPass::deserialize(definition) {
+                return Some(locate_field::<StandardPass>(definition, path, "name", e));
+            }
+        }
+        "SequencePass" => {
+            if let Some(Value::Array(sequence)) = definition.get("sequence") {
+                for (index, pass) in sequence.iter().enumerate() {
+                    let found = locate_pass(pass, format!("{path}.sequence[{index}]"));
+                    if found.is_some() {
+                        return found;
+                    }
+                }
+            }
+        }
+        "RepeatPass" | "RepeatUntilSatisfiedPass" => {
+            if let Some(body) = definition.get("body") {
+                let found = locate_pass(body, format!("{path}.body"));
+                if found.is_some() {
+                    return found;
+                }
+            }
+            if let Some(predicate) = definition.get("predicate") {
+                if let Err(e) = Predicate::deserialize(predicate) {
+                    let path = format!("{path}.predicate");
+                    return Some(locate_field::<Predicate>(predicate, path, "type", e));
+                }
+            }
+        }
+        _ => {}
+    }
+    Some((path, e))
+}
+
+/// Returns the path to the field of the object `value` responsible for the
+/// error `e` when deserializing it as a `T`, or `path` itself if no single
+/// field is.
+///
+/// A field is responsible if removing it changes the outcome. The `tag` field
+/// naming the variant is never blamed, and missing fields are reported on the
+/// object.
+fn locate_field<T: DeserializeOwned>(
+    value: &Value,
+    path: String,
+    tag: &str,
+    e: serde_json::Error,
+) -> (String, serde_json::Error) {
+    let Value::Object(fields) = value else {
+        return (path, e);
+    };
+    if e.to_string().starts_with("missing field") {
+        return (path, e);
+    }
+    for key in fields.keys().filter(|key| *key != tag) {
+        let mut without = fields.clone();
+        without.remove(key);
+        let changed = match T::deserialize(&Value::Object(without)) {
+            Ok(_) => true,
+            Err(other) => other.to_string() != e.to_string(),
+        };
+        if changed {
+            return (format!("{path}.{key}"), e);
+        }
+    }
+    (path, e)
+}
@@ -0,0 +1,53 @@
+//! Lenient deserialization of numeric pass options.
+//!
+//! Some JSON producers write every number as a float, so integer options may
+//! arrive as e.g. `2.0`. Floating point options already accept integers.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Unexpected, Visitor};
+use serde::Deserializer;
+
+/// Deserializes an integer, also accepting floats with no fractional part.
+pub(crate) fn deserialize_integer<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<u64> + TryFrom<i64>,
+{
+    deserializer.deserialize_any(IntegerVisitor(PhantomData))
+}
+
+/// Visitor for [`deserialize_integer`].
+struct IntegerVisitor<T>(PhantomData<T>);
+
+impl<T: TryFrom<u64> + TryFrom<i64>> Visitor<'_> for IntegerVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an integer in range")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+        T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> {
+        T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<T, E> {
+        let invalid = || E::invalid_value(Unexpected::Float(v), &self);
+        if v.fract() != 0.0 || !v.is_finite() {
+            return Err(invalid());
+        }
+        // Casts saturate, so out-of-range values are rejected by `try_from`
+        // or by the round-trip check.
+        let converted = if v >= 0.0 {
+            T::try_from(v as u64).ok().filter(|_| v as u64 as f64 == v)
+        } else {
+            T::try_from(v as i64).ok().filter(|_| v as i64 as f64 == v)
+        };
+        converted.ok_or_else(invalid)
+    }
+}
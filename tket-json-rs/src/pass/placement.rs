@@ -0,0 +1,52 @@
+//! Serialized definition for TKET placements.
+//!
+//! Based on the `placement_v1` schema.
+//! <https://github.com/CQCL/tket/blob/main/schemas/placement_v1.json>
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pass::Architecture;
+
+/// A strategy for placing logical qubits onto the nodes of an architecture.
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Placement {
+    /// The placement strategy.
+    #[serde(rename = "type")]
+    pub placement_type: PlacementType,
+    /// The architecture to place the qubits on.
+    pub architecture: Architecture,
+    /// Strategy-specific configuration, such as the search limits of a
+    /// [`PlacementType::GraphPlacement`] or the device characterisation of a
+    /// [`PlacementType::NoiseAwarePlacement`].
+    #[serde(flatten)]
+    pub parameters: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The placement strategies supported by pytket.
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PlacementType {
+    /// Place qubits in the order of the architecture nodes.
+    Placement,
+    /// Place qubits along lines of the architecture.
+    LinePlacement,
+    /// Place qubits by matching the interaction graph onto the architecture.
+    GraphPlacement,
+    /// Place qubits using device error rates.
+    NoiseAwarePlacement,
+}
+
+impl Placement {
+    /// Create a new placement with the default configuration.
+    pub fn new(placement_type: PlacementType, architecture: Architecture) -> Self {
+        Self {
+            placement_type,
+            architecture,
+            parameters: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Serialized definition for TKET predicates.
+//!
+//! Based on the `predicate_v1` schema.
+//! <https://github.com/CQCL/tket/blob/main/schemas/predicate_v1.json>
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::optype::OpType;
+use crate::pass::Architecture;
+use crate::register::ElementId;
+
+/// A property of a circuit that may be checked, required, or guaranteed by a
+/// pass.
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+#[non_exhaustive]
+pub enum Predicate {
+    /// All operations are in the given set of types.
+    GateSetPredicate {
+        /// The allowed operation types.
+        allowed_types: Vec<OpType>,
+    },
+    /// No operation is classically controlled.
+    NoClassicalControlPredicate,
+    /// No classical bit is read after being written.
+    NoFastFeedforwardPredicate,
+    /// The circuit has no classical bits.
+    NoClassicalBitsPredicate,
+    /// There are no wire swaps in the circuit.
+    NoWireSwapsPredicate,
+    /// All operations act on at most two qubits.
+    MaxTwoQubitGatesPredicate,
+    /// The circuit consists only of Clifford operations.
+    CliffordCircuitPredicate,
+    /// All qubits and bits belong to the default registers.
+    DefaultRegisterPredicate,
+    /// The circuit has at most a given number of qubits.
+    MaxNQubitsPredicate {
+        /// The maximum number of qubits.
+        #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+        n_qubits: u32,
+    },
+    /// The circuit has at most a given number of classical registers.
+    MaxNClRegPredicate {
+        /// The maximum number of classical registers.
+        #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+        n_cl_reg: u32,
+    },
+    /// The circuit has no barriers.
+    NoBarriersPredicate,
+    /// All measurements occur at the end of the circuit.
+    NoMidMeasurePredicate,
+    /// The circuit has no symbolic parameters.
+    NoSymbolsPredicate,
+    /// All `PhasedX` operations are global.
+    GlobalPhasedXPredicate,
+    /// All TK2 gates have normalised parameters.
+    NormalisedTK2Predicate,
+    /// All measurements can be commuted to the end of the circuit.
+    CommutableMeasuresPredicate,
+    /// All qubits are placed on the given set of nodes.
+    PlacementPredicate {
+        /// The allowed nodes.
+        node_set: Vec<ElementId>,
+    },
+    /// All multi-qubit operations act on connected nodes of an architecture.
+    ConnectivityPredicate {
+        /// The target architecture.
+        architecture: Architecture,
+    },
+    /// All two-qubit operations respect the edge directions of an
+    /// architecture.
+    DirectednessPredicate {
+        /// The target architecture.
+        architecture: Architecture,
+    },
+    /// A predicate defined by a user function.
+    ///
+    /// These cannot be serialized by pytket, this variant is only a marker.
+    UserDefinedPredicate,
+}
@@ -0,0 +1,77 @@
+//! Name-based lookup of standard passes.
+//!
+//! Tools that only learn the name of a pass at runtime can use this module
+//! to decode its configuration, without matching on the serialized
+//! `"name"` tag themselves.
+
+use derive_more::{Display, Error};
+use serde_json::Value;
+use strum::VariantNames;
+
+use crate::pass::standard::StandardPass;
+
+/// Error returned by [`deserialize_standard_pass`].
+#[derive(Debug, Display, Error)]
+#[non_exhaustive]
+pub enum RegistryError {
+    /// No standard pass has the given name.
+    #[display("unknown standard pass {name}")]
+    UnknownPass {
+        /// The requested pass name.
+        name: String,
+    },
+    /// The pass configuration is not a JSON object.
+    #[display("the configuration of {name} must be a JSON object")]
+    NotAnObject {
+        /// The requested pass name.
+        name: String,
+    },
+    /// The pass configuration does not match the pass definition.
+    #[display("invalid configuration for {name}: {source}")]
+    InvalidConfig {
+        /// The requested pass name.
+        name: String,
+        /// The deserialization error.
+        source: serde_json::Error,
+    },
+}
+
+/// Returns the names of all the known standard passes.
+pub fn known_pass_names() -> &'static [&'static str] {
+    StandardPass::VARIANTS
+}
+
+/// Returns `true` if `name` is the name of a known standard pass.
+pub fn is_known_pass(name: &str) -> bool {
+    known_pass_names().contains(&name)
+}
+
+/// Deserializes the standard pass called `name` from its configuration.
+///
+/// The configuration holds the fields of the pass, as in its serialized
+/// form, and may omit the `"name"` tag. Passes without configuration also
+/// accept `null`.
+pub fn deserialize_standard_pass(
+    name: &str,
+    config: &Value,
+) -> Result<StandardPass, RegistryError> {
+    if !is_known_pass(name) {
+        return Err(RegistryError::UnknownPass {
+            name: name.to_string(),
+        });
+    }
+    let mut fields = match config {
+        Value::Object(fields) => fields.clone(),
+        Value::Null => Default::default(),
+        _ => {
+            return Err(RegistryError::NotAnObject {
+                name: name.to_string(),
+            })
+        }
+    };
+    fields.insert("name".to_string(), Value::String(name.to_string()));
+    serde_json::from_value(Value::Object(fields)).map_err(|source| RegistryError::InvalidConfig {
+        name: name.to_string(),
+        source,
+    })
+}
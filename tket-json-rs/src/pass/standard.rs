@@ -1,10 +1,19 @@
 //! Standard pass definitions.
+//!
+//! The canonical field names follow the `compiler_pass_v1` schema shipped with
+//! pytket 2.x, and are always used when serializing. Spellings emitted by
+//! older pytket 1.x releases, and the keyword argument names of the matching
+//! pytket functions where they differ (e.g. `cx_fidelity` or `strat`), are
+//! accepted as serde aliases when deserializing. Fields that older releases
+//! did not emit fall back to pytket's defaults.
 
 #[cfg(feature = "schemars")]
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use strum::{EnumString, VariantNames};
 
-use crate::pass::{Architecture, Placement};
+use crate::pass::architecture::MappingError;
+use crate::pass::{Architecture, Placement, Predicate};
 use crate::{register::ElementId, SerialCircuit};
 
 /// A serialized standard pass.
@@ -13,7 +22,7 @@ use crate::{register::ElementId, SerialCircuit};
 // `RebaseCustom`, but they are not actually supported by pytket so we have
 // removed them here.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, VariantNames)]
 #[serde(tag = "name")]
 #[non_exhaustive]
 pub enum StandardPass {
@@ -133,6 +142,422 @@ pub enum StandardPass {
     FlattenRelabelRegistersPass(FlattenRelabelRegistersPass),
 }
 
+/// The broad category of a [`StandardPass`], see [`StandardPass::category`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PassCategory {
+    /// Rewrites the circuit into a target gate set.
+    Rebase,
+    /// Merges sequences of gates into fewer, more general gates.
+    Squash,
+    /// Makes the circuit conform to the connectivity of an architecture.
+    Routing,
+    /// Assigns the logical qubits of the circuit to physical nodes.
+    Placement,
+    /// Reduces the size or depth of the circuit.
+    Optimisation,
+    /// Expands gates or boxes into simpler operations.
+    Decomposition,
+    /// Removes or flattens structure that does not affect the computation.
+    Cleanup,
+    /// Any other transformation.
+    Other,
+}
+
+/// A rough complexity class of a [`StandardPass`], see
+/// [`StandardPass::cost_hint`].
+///
+/// Classes describe how the running time grows with the size of the circuit
+/// in typical cases, and are only meant for planning compilation pipelines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum CostHint {
+    /// A single sweep over the circuit, with local rewrites.
+    Linear,
+    /// Repeated sweeps, or rewrites that consider many gates at once.
+    Quadratic,
+    /// Searches whose cost can grow exponentially with the circuit size.
+    Exponential,
+}
+
+impl StandardPass {
+    /// Returns the circuits embedded in the pass definition.
+    ///
+    /// These are the replacement and reference circuits of passes such as
+    /// [`StandardPass::DecomposeSwapsToCircuit`] or [`StandardPass::ContextSimp`].
+    pub fn circuits(&self) -> Vec<&SerialCircuit> {
+        match self {
+            StandardPass::DecomposeSwapsToCircuit(pass) => vec![pass.swap_replacement()],
+            StandardPass::ContextSimp(pass) => vec![pass.x_circuit()],
+            StandardPass::SimplifyInitial(pass) => pass.x_circuit().into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the two-qubit gate targeted by the pass, or `None` if the pass
+    /// has no such option.
+    pub fn target_2qb_gate(&self) -> Option<TargetTwoQubitGate> {
+        match self {
+            StandardPass::KAKDecomposition(KAKDecomposition {
+                target_2qb_gate, ..
+            })
+            | StandardPass::FullPeepholeOptimise(FullPeepholeOptimise {
+                target_2qb_gate, ..
+            })
+            | StandardPass::CliffordSimp(CliffordSimp {
+                target_2qb_gate, ..
+            }) => Some(target_2qb_gate.clone()),
+            _ => None,
+        }
+    }
+
+    /// Sets the two-qubit gate targeted by the pass.
+    ///
+    /// Returns `false`, leaving the pass unchanged, if it has no such option.
+    pub fn set_target_2qb_gate(&mut self, gate: TargetTwoQubitGate) -> bool {
+        match self {
+            StandardPass::KAKDecomposition(KAKDecomposition {
+                target_2qb_gate, ..
+            })
+            | StandardPass::FullPeepholeOptimise(FullPeepholeOptimise {
+                target_2qb_gate, ..
+            })
+            | StandardPass::CliffordSimp(CliffordSimp {
+                target_2qb_gate, ..
+            }) => {
+                *target_2qb_gate = gate;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Replaces the architecture targeted by the pass.
+    ///
+    /// This applies to the routing, mapping and placement passes, and to
+    /// [`StandardPass::DecomposeSwapsToCXs`]. The architecture of the
+    /// placement nested in the mapping passes is replaced as well. Returns
+    /// `false`, leaving the pass unchanged, if it has no architecture.
+    pub fn set_architecture(&mut self, arch: &Architecture) -> bool {
+        match self {
+            StandardPass::RoutingPass(RoutingPass { architecture, .. })
+            | StandardPass::CustomRoutingPass(CustomRoutingPass { architecture, .. })
+            | StandardPass::NaivePlacementPass(NaivePlacementPass { architecture })
+            | StandardPass::DecomposeSwapsToCXs(DecomposeSwapsToCXs { architecture, .. })
+            | StandardPass::DefaultMappingPass(DefaultMappingPass { architecture, .. })
+            | StandardPass::PlacementPass(PlacementPass {
+                placement: Placement { architecture, .. },
+            }) => {
+                *architecture = arch.clone();
+                true
+            }
+            StandardPass::FullMappingPass(FullMappingPass {
+                architecture,
+                placement,
+                ..
+            })
+            | StandardPass::CXMappingPass(CXMappingPass {
+                architecture,
+                placement,
+                ..
+            }) => {
+                *architecture = arch.clone();
+                placement.architecture = arch.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the broad category of the pass, for grouping passes in user
+    /// interfaces.
+    pub fn category(&self) -> PassCategory {
+        match self {
+            StandardPass::RebaseCustomViaTK2
+            | StandardPass::AutoRebase(_)
+            | StandardPass::RebaseTket
+            | StandardPass::RebaseUFR
+            | StandardPass::RxFromSX => PassCategory::Rebase,
+            StandardPass::AutoSquash(_)
+            | StandardPass::SquashTK1
+            | StandardPass::SquashRzPhasedX
+            | StandardPass::ThreeQubitSquash(_)
+            | StandardPass::EulerAngleReduction(_)
+            | StandardPass::PauliSquash(_) => PassCategory::Squash,
+            StandardPass::RoutingPass(_)
+            | StandardPass::CustomRoutingPass(_)
+            | StandardPass::FullMappingPass(_)
+            | StandardPass::DefaultMappingPass(_)
+            | StandardPass::CXMappingPass(_) => PassCategory::Routing,
+            StandardPass::PlacementPass(_) | StandardPass::NaivePlacementPass(_) => {
+                PassCategory::Placement
+            }
+            StandardPass::CommuteThroughMultis
+            | StandardPass::PeepholeOptimise2Q(_)
+            | StandardPass::RemoveRedundancies
+            | StandardPass::SynthesiseTK
+            | StandardPass::SynthesiseTket
+            | StandardPass::SynthesiseOQC
+            | StandardPass::ZZPhaseToRz
+            | StandardPass::SimplifyMeasured
+            | StandardPass::KAKDecomposition(_)
+            | StandardPass::FullPeepholeOptimise(_)
+            | StandardPass::CliffordSimp(_)
+            | StandardPass::OptimisePhaseGadgets(_)
+            | StandardPass::OptimisePairwiseGadgets
+            | StandardPass::PauliSimp(_)
+            | StandardPass::PauliExponentials(_)
+            | StandardPass::GuidedPauliSimp(_)
+            | StandardPass::SimplifyInitial(_)
+            | StandardPass::ContextSimp(_)
+            | StandardPass::GreedyPauliSimp(_) => PassCategory::Optimisation,
+            StandardPass::DecomposeArbitrarilyControlledGates
+            | StandardPass::DecomposeBoxes(_)
+            | StandardPass::DecomposeMultiQubitsCX
+            | StandardPass::DecomposeSingleQubitsTK1
+            | StandardPass::DecomposeBridges
+            | StandardPass::DecomposeSwapsToCXs(_)
+            | StandardPass::DecomposeSwapsToCircuit(_)
+            | StandardPass::DecomposeTK2(_)
+            | StandardPass::CnXPairwiseDecomposition => PassCategory::Decomposition,
+            StandardPass::FlattenRegisters
+            | StandardPass::RemoveDiscarded
+            | StandardPass::RemoveBarriers
+            | StandardPass::RemovePhaseOps
+            | StandardPass::RemoveImplicitQubitPermutation
+            | StandardPass::FlattenRelabelRegistersPass(_) => PassCategory::Cleanup,
+            StandardPass::DelayMeasures(_)
+            | StandardPass::ComposePhasePolyBoxes(_)
+            | StandardPass::RenameQubitsPass(_)
+            | StandardPass::NormaliseTK2
+            | StandardPass::RoundAngles(_) => PassCategory::Other,
+        }
+    }
+
+    /// Returns a rough estimate of the cost of running the pass.
+    ///
+    /// This is advisory metadata based on the known behaviour of the pass in
+    /// tket, and does not depend on its parameters.
+    pub fn cost_hint(&self) -> CostHint {
+        match self {
+            StandardPass::RebaseCustomViaTK2
+            | StandardPass::AutoRebase(_)
+            | StandardPass::RebaseTket
+            | StandardPass::RebaseUFR
+            | StandardPass::RxFromSX
+            | StandardPass::AutoSquash(_)
+            | StandardPass::SquashTK1
+            | StandardPass::SquashRzPhasedX
+            | StandardPass::EulerAngleReduction(_)
+            | StandardPass::RemoveRedundancies
+            | StandardPass::ZZPhaseToRz
+            | StandardPass::NaivePlacementPass(_)
+            | StandardPass::DecomposeArbitrarilyControlledGates
+            | StandardPass::DecomposeBoxes(_)
+            | StandardPass::DecomposeMultiQubitsCX
+            | StandardPass::DecomposeSingleQubitsTK1
+            | StandardPass::DecomposeBridges
+            | StandardPass::DecomposeSwapsToCXs(_)
+            | StandardPass::DecomposeSwapsToCircuit(_)
+            | StandardPass::DecomposeTK2(_)
+            | StandardPass::CnXPairwiseDecomposition
+            | StandardPass::FlattenRegisters
+            | StandardPass::RemoveDiscarded
+            | StandardPass::RemoveBarriers
+            | StandardPass::RemovePhaseOps
+            | StandardPass::RemoveImplicitQubitPermutation
+            | StandardPass::FlattenRelabelRegistersPass(_)
+            | StandardPass::DelayMeasures(_)
+            | StandardPass::RenameQubitsPass(_)
+            | StandardPass::NormaliseTK2
+            | StandardPass::RoundAngles(_)
+            | StandardPass::SimplifyInitial(_)
+            | StandardPass::SimplifyMeasured => CostHint::Linear,
+            StandardPass::ThreeQubitSquash(_)
+            | StandardPass::PauliSquash(_)
+            | StandardPass::RoutingPass(_)
+            | StandardPass::CustomRoutingPass(_)
+            | StandardPass::CommuteThroughMultis
+            | StandardPass::PeepholeOptimise2Q(_)
+            | StandardPass::SynthesiseTK
+            | StandardPass::SynthesiseTket
+            | StandardPass::SynthesiseOQC
+            | StandardPass::KAKDecomposition(_)
+            | StandardPass::FullPeepholeOptimise(_)
+            | StandardPass::CliffordSimp(_)
+            | StandardPass::OptimisePhaseGadgets(_)
+            | StandardPass::OptimisePairwiseGadgets
+            | StandardPass::PauliSimp(_)
+            | StandardPass::PauliExponentials(_)
+            | StandardPass::GuidedPauliSimp(_)
+            | StandardPass::ContextSimp(_)
+            | StandardPass::ComposePhasePolyBoxes(_) => CostHint::Quadratic,
+            StandardPass::PlacementPass(_)
+            | StandardPass::FullMappingPass(_)
+            | StandardPass::DefaultMappingPass(_)
+            | StandardPass::CXMappingPass(_)
+            | StandardPass::GreedyPauliSimp(_) => CostHint::Exponential,
+        }
+    }
+
+    /// Returns `true` if applying the pass twice in a row has the same effect
+    /// as applying it once.
+    ///
+    /// This holds for [`StandardPass::RemoveBarriers`],
+    /// [`StandardPass::RemovePhaseOps`], [`StandardPass::RemoveDiscarded`],
+    /// [`StandardPass::RemoveImplicitQubitPermutation`],
+    /// [`StandardPass::RemoveRedundancies`],
+    /// [`StandardPass::FlattenRegisters`], [`StandardPass::DecomposeBridges`]
+    /// and [`StandardPass::RebaseTket`]. Every other pass conservatively
+    /// returns `false`.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self,
+            StandardPass::RemoveBarriers
+                | StandardPass::RemovePhaseOps
+                | StandardPass::RemoveDiscarded
+                | StandardPass::RemoveImplicitQubitPermutation
+                | StandardPass::RemoveRedundancies
+                | StandardPass::FlattenRegisters
+                | StandardPass::DecomposeBridges
+                | StandardPass::RebaseTket
+        )
+    }
+
+    /// Returns the predicates that a circuit must satisfy before applying
+    /// this pass.
+    ///
+    /// Only the routing and mapping passes currently report their
+    /// requirements. Other passes return an empty list, which does not imply
+    /// that they accept any circuit.
+    pub fn required_predicates(&self) -> Vec<Predicate> {
+        match self {
+            StandardPass::RoutingPass(_)
+            | StandardPass::CustomRoutingPass(_)
+            | StandardPass::FullMappingPass(_)
+            | StandardPass::DefaultMappingPass(_)
+            | StandardPass::CXMappingPass(_) => vec![Predicate::MaxTwoQubitGatesPredicate],
+            StandardPass::DecomposeSwapsToCXs(pass) => vec![Predicate::ConnectivityPredicate {
+                architecture: pass.architecture.clone(),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the predicates that a circuit is guaranteed to satisfy after
+    /// applying this pass.
+    ///
+    /// As with [`StandardPass::required_predicates`], only the routing and
+    /// mapping passes currently report their guarantees.
+    pub fn guaranteed_predicates(&self) -> Vec<Predicate> {
+        let connected = |architecture: &Architecture| Predicate::ConnectivityPredicate {
+            architecture: architecture.clone(),
+        };
+        match self {
+            StandardPass::RoutingPass(RoutingPass { architecture, .. })
+            | StandardPass::CustomRoutingPass(CustomRoutingPass { architecture, .. })
+            | StandardPass::FullMappingPass(FullMappingPass { architecture, .. })
+            | StandardPass::DefaultMappingPass(DefaultMappingPass { architecture, .. }) => {
+                vec![connected(architecture)]
+            }
+            StandardPass::CXMappingPass(pass) => {
+                let mut predicates = vec![connected(&pass.architecture)];
+                if pass.directed {
+                    predicates.push(Predicate::DirectednessPredicate {
+                        architecture: pass.architecture.clone(),
+                    });
+                }
+                predicates
+            }
+            StandardPass::DecomposeSwapsToCXs(pass) if pass.directed => {
+                vec![Predicate::DirectednessPredicate {
+                    architecture: pass.architecture.clone(),
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl RoutingPass {
+    /// Checks that the pass' architecture can hold every qubit of the circuit.
+    pub fn check_against<P>(&self, circuit: &SerialCircuit<P>) -> Result<(), MappingError> {
+        self.architecture.check_against(circuit)
+    }
+
+    /// Converts the pass into a full mapping pass with the same architecture
+    /// and routing configuration, placing the qubits with `placement` first.
+    ///
+    /// The placement's own architecture is kept as given.
+    pub fn into_full_mapping(self, placement: Placement) -> FullMappingPass {
+        FullMappingPass {
+            architecture: self.architecture,
+            placement,
+            routing_config: self.routing_config,
+        }
+    }
+}
+
+impl FullMappingPass {
+    /// Checks that the pass' architecture can hold every qubit of the circuit.
+    pub fn check_against<P>(&self, circuit: &SerialCircuit<P>) -> Result<(), MappingError> {
+        self.architecture.check_against(circuit)
+    }
+}
+
+impl CXMappingPass {
+    /// Checks that the pass' architecture can hold every qubit of the circuit.
+    pub fn check_against<P>(&self, circuit: &SerialCircuit<P>) -> Result<(), MappingError> {
+        self.architecture.check_against(circuit)
+    }
+}
+
+/// Passes with a configurable `allow_swaps` option.
+///
+/// Passes that allow swaps may introduce implicit wire swaps when
+/// simplifying the circuit.
+pub trait HasAllowSwaps {
+    /// Returns whether the pass may introduce implicit wire swaps, or `None`
+    /// if the pass has no such option.
+    fn allow_swaps(&self) -> Option<bool>;
+
+    /// Sets whether the pass may introduce implicit wire swaps.
+    ///
+    /// Returns `false`, leaving the pass unchanged, if it has no such option.
+    fn set_allow_swaps(&mut self, allow_swaps: bool) -> bool;
+}
+
+impl HasAllowSwaps for StandardPass {
+    fn allow_swaps(&self) -> Option<bool> {
+        match self {
+            StandardPass::AutoRebase(AutoRebase { allow_swaps, .. })
+            | StandardPass::PeepholeOptimise2Q(PeepholeOptimise2Q { allow_swaps, .. })
+            | StandardPass::KAKDecomposition(KAKDecomposition { allow_swaps, .. })
+            | StandardPass::ThreeQubitSquash(ThreeQubitSquash { allow_swaps, .. })
+            | StandardPass::FullPeepholeOptimise(FullPeepholeOptimise { allow_swaps, .. })
+            | StandardPass::CliffordSimp(CliffordSimp { allow_swaps, .. })
+            | StandardPass::DecomposeTK2(DecomposeTK2 { allow_swaps, .. }) => Some(*allow_swaps),
+            _ => None,
+        }
+    }
+
+    fn set_allow_swaps(&mut self, value: bool) -> bool {
+        match self {
+            StandardPass::AutoRebase(AutoRebase { allow_swaps, .. })
+            | StandardPass::PeepholeOptimise2Q(PeepholeOptimise2Q { allow_swaps, .. })
+            | StandardPass::KAKDecomposition(KAKDecomposition { allow_swaps, .. })
+            | StandardPass::ThreeQubitSquash(ThreeQubitSquash { allow_swaps, .. })
+            | StandardPass::FullPeepholeOptimise(FullPeepholeOptimise { allow_swaps, .. })
+            | StandardPass::CliffordSimp(CliffordSimp { allow_swaps, .. })
+            | StandardPass::DecomposeTK2(DecomposeTK2 { allow_swaps, .. }) => {
+                *allow_swaps = value;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 /// Automatically rebase to a given gate set.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -156,8 +581,16 @@ pub struct AutoSquash {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct DecomposeBoxes {
     /// Operation types excluded from decomposition.
+    ///
+    /// Empty if missing when deserializing. It is always serialized, as the
+    /// pytket schema requires it.
+    #[serde(default)]
     pub excluded_types: Vec<String>,
     /// Operation groups excluded from decomposition.
+    ///
+    /// Empty if missing when deserializing. It is always serialized, as the
+    /// pytket schema requires it.
+    #[serde(default)]
     pub excluded_opgroups: Vec<String>,
     /// Operation types explicitly included in decomposition.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -180,10 +613,12 @@ pub struct PeepholeOptimise2Q {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct KAKDecomposition {
     /// Fidelity threshold to preserve semantics.
+    #[serde(alias = "cx_fidelity")]
     pub fidelity: f64,
     /// Whether swaps are allowed during optimisation.
     pub allow_swaps: bool,
     /// Target native 2-qubit gate.
+    #[serde(default, skip_serializing_if = "crate::pass::compact::skip_default")]
     pub target_2qb_gate: TargetTwoQubitGate,
 }
 
@@ -202,6 +637,7 @@ pub struct FullPeepholeOptimise {
     /// Whether swaps are allowed.
     pub allow_swaps: bool,
     /// Target native 2-qubit gate.
+    #[serde(default, skip_serializing_if = "crate::pass::compact::skip_default")]
     pub target_2qb_gate: TargetTwoQubitGate,
 }
 
@@ -210,6 +646,7 @@ pub struct FullPeepholeOptimise {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct ComposePhasePolyBoxes {
     /// Minimal number of CX gates per phase.
+    #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
     pub min_size: u32,
 }
 
@@ -218,10 +655,13 @@ pub struct ComposePhasePolyBoxes {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct EulerAngleReduction {
     /// Axis used for the P rotation.
+    #[serde(alias = "p")]
     pub euler_p: RotationAxis,
     /// Axis used for the Q rotation.
+    #[serde(alias = "q")]
     pub euler_q: RotationAxis,
     /// Whether to enforce strict P-Q-P reductions.
+    #[serde(alias = "strict")]
     pub euler_strict: bool,
 }
 
@@ -276,6 +716,7 @@ pub struct CliffordSimp {
     /// Whether swaps can be introduced.
     pub allow_swaps: bool,
     /// Target native 2-qubit gate.
+    #[serde(default, skip_serializing_if = "crate::pass::compact::skip_default")]
     pub target_2qb_gate: TargetTwoQubitGate,
 }
 
@@ -286,6 +727,7 @@ pub struct DecomposeSwapsToCXs {
     /// Target architecture.
     pub architecture: Architecture,
     /// Whether the architecture edges are directed.
+    #[serde(alias = "respect_direction")]
     pub directed: bool,
 }
 
@@ -297,6 +739,29 @@ pub struct DecomposeSwapsToCircuit {
     pub swap_replacement: Box<SerialCircuit>,
 }
 
+impl DecomposeSwapsToCircuit {
+    /// Creates the pass configuration from its swap replacement circuit.
+    ///
+    /// ```
+    /// use tket_json_rs::pass::standard::DecomposeSwapsToCircuit;
+    /// use tket_json_rs::SerialCircuit;
+    ///
+    /// let replacement = SerialCircuit::new(Some("swap".to_string()), "0".to_string());
+    /// let pass = DecomposeSwapsToCircuit::new(replacement);
+    /// assert_eq!(pass.swap_replacement().name.as_deref(), Some("swap"));
+    /// ```
+    pub fn new(swap_replacement: SerialCircuit) -> Self {
+        Self {
+            swap_replacement: Box::new(swap_replacement),
+        }
+    }
+
+    /// Returns the replacement circuit for a swap.
+    pub fn swap_replacement(&self) -> &SerialCircuit {
+        &self.swap_replacement
+    }
+}
+
 /// Phase gadget optimisation configuration.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -310,6 +775,7 @@ pub struct OptimisePhaseGadgets {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct PauliSynthesisConfig {
     /// Strategy for synthesising Pauli gadgets.
+    #[serde(alias = "strat")]
     pub pauli_synth_strat: PauliSynthStrategy,
     /// Preferred CX configuration.
     pub cx_config: CxConfig,
@@ -328,6 +794,32 @@ pub struct SimplifyInitial {
     pub x_circuit: Option<Box<SerialCircuit>>,
 }
 
+impl SimplifyInitial {
+    /// Sets the witness circuit.
+    ///
+    /// ```
+    /// use tket_json_rs::pass::standard::SimplifyInitial;
+    /// use tket_json_rs::SerialCircuit;
+    ///
+    /// let pass = SimplifyInitial {
+    ///     allow_classical: false,
+    ///     create_all_qubits: true,
+    ///     x_circuit: None,
+    /// }
+    /// .with_x_circuit(SerialCircuit::new(None, "0".to_string()));
+    /// assert!(pass.x_circuit().is_some());
+    /// ```
+    pub fn with_x_circuit(mut self, x_circuit: SerialCircuit) -> Self {
+        self.x_circuit = Some(Box::new(x_circuit));
+        self
+    }
+
+    /// Returns the witness circuit, if any.
+    pub fn x_circuit(&self) -> Option<&SerialCircuit> {
+        self.x_circuit.as_deref()
+    }
+}
+
 /// Full mapping pass configuration.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -361,6 +853,7 @@ pub struct CXMappingPass {
     /// Routing configuration.
     pub routing_config: RoutingConfig,
     /// Whether the architecture is directed.
+    #[serde(alias = "directed_cx")]
     pub directed: bool,
     /// Whether to delay measurements.
     pub delay_measures: bool,
@@ -376,6 +869,49 @@ pub struct ContextSimp {
     pub x_circuit: Box<SerialCircuit>,
 }
 
+impl ContextSimp {
+    /// Creates the pass configuration from its reference circuit.
+    ///
+    /// ```
+    /// use tket_json_rs::pass::standard::ContextSimp;
+    /// use tket_json_rs::SerialCircuit;
+    ///
+    /// let pass = ContextSimp::new(true, SerialCircuit::new(None, "0".to_string()));
+    /// assert!(pass.allow_classical);
+    /// assert!(pass.x_circuit().commands.is_empty());
+    /// ```
+    pub fn new(allow_classical: bool, x_circuit: SerialCircuit) -> Self {
+        Self {
+            allow_classical,
+            x_circuit: Box::new(x_circuit),
+        }
+    }
+
+    /// Returns the reference circuit required by the pass.
+    ///
+    /// ```
+    /// use tket_json_rs::pass::standard::StandardPass;
+    ///
+    /// let pass: StandardPass = serde_json::from_str(r#"{
+    ///     "name": "ContextSimp",
+    ///     "allow_classical": true,
+    ///     "x_circuit": {
+    ///         "bits": [],
+    ///         "commands": [{"args": [["q", [0]]], "op": {"type": "X"}}],
+    ///         "implicit_permutation": [],
+    ///         "phase": "0",
+    ///         "qubits": [["q", [0]]]
+    ///     }
+    /// }"#).unwrap();
+    ///
+    /// let StandardPass::ContextSimp(context_simp) = &pass else { unreachable!() };
+    /// assert_eq!(context_simp.x_circuit().commands.len(), 1);
+    /// ```
+    pub fn x_circuit(&self) -> &SerialCircuit {
+        &self.x_circuit
+    }
+}
+
 /// Decompose TK2 configuration.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -394,6 +930,7 @@ pub struct DecomposeTK2 {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct RoundAngles {
     /// Level of precision.
+    #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
     pub n: i64,
     /// Whether only zero rounding is allowed.
     pub only_zeros: bool,
@@ -457,11 +994,74 @@ pub struct QubitMapping(pub ElementId, pub ElementId);
 pub type RoutingConfig = Vec<RoutingMethod>;
 
 /// Routing method descriptor.
+///
+/// Methods whose `name` is not one of the typed variants are kept as
+/// [`RoutingMethod::Other`]. A typed method with missing or invalid fields is
+/// an error.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
-pub struct RoutingMethod {
-    /// Name of the routing method.
-    pub name: String,
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, VariantNames)]
+#[serde(tag = "name", remote = "Self")]
+#[non_exhaustive]
+pub enum RoutingMethod {
+    /// The base routing method, which does not modify the circuit.
+    RoutingMethod,
+    /// Route by inserting swaps chosen with a fixed lookahead.
+    LexiRouteRoutingMethod {
+        /// Number of layers of gates to look ahead when choosing swaps.
+        #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+        depth: u32,
+    },
+    /// Assign unplaced qubits to the nodes used by the next gates.
+    LexiLabellingMethod,
+    /// Route architecture-aware synthesised phase polynomial boxes.
+    AASRouteRoutingMethod {
+        /// Width of the partitions of the phase polynomial.
+        #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+        aaswidth: u32,
+        /// The CNOT synthesis strategy, as the numeric value of pytket's
+        /// `CNotSynthType` (0 for `SWAP`, 1 for `HamPath`, 2 for `Rec`).
+        #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+        cnotsynthtype: u32,
+    },
+    /// Place the qubits of phase polynomial boxes before AAS routing.
+    AASLabellingMethod,
+    /// Commute multi-qubit gates forward when they are already routed.
+    MultiGateReorderRoutingMethod {
+        /// Maximum number of layers of gates considered.
+        #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+        depth: u32,
+        /// Maximum number of gates considered.
+        #[serde(deserialize_with = "crate::pass::numeric::deserialize_integer")]
+        size: u32,
+    },
+    /// Decompose boxes that cannot be routed directly.
+    BoxDecompositionRoutingMethod,
+    /// A routing method not known to this crate, such as a user-defined one.
+    ///
+    /// Holds the raw fields of the method, including its `name`.
+    #[serde(untagged, skip_deserializing)]
+    Other(serde_json::Map<String, serde_json::Value>),
+}
+
+impl Serialize for RoutingMethod {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RoutingMethod::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RoutingMethod {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = serde_json::Map::deserialize(deserializer)?;
+        let typed = fields
+            .get("name")
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|name| name != "Other" && RoutingMethod::VARIANTS.contains(&name));
+        if !typed {
+            return Ok(RoutingMethod::Other(fields));
+        }
+        RoutingMethod::deserialize(serde_json::Value::Object(fields))
+            .map_err(serde::de::Error::custom)
+    }
 }
 
 /// Configuration for decomposing TK2 gates.
@@ -469,19 +1069,22 @@ pub struct RoutingMethod {
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct DecomposeTK2Fidelities {
     /// Optional CX fidelity.
-    #[serde(rename = "CX", skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "CX", alias = "CX_fidelity")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cx: Option<f64>,
     /// Optional ZZMax fidelity.
-    #[serde(rename = "ZZMax", skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ZZMax", alias = "ZZMax_fidelity")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub zz_max: Option<f64>,
     /// Optional ZZPhase fidelity.
-    #[serde(rename = "ZZPhase", skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ZZPhase", alias = "ZZPhase_fidelity")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub zz_phase: Option<f64>,
 }
 
 /// Rotation axes used during Euler angle reduction.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, EnumString, derive_more::Display)]
 pub enum RotationAxis {
     /// Rotation around the X axis.
     Rx,
@@ -493,9 +1096,12 @@ pub enum RotationAxis {
 
 /// Target native two-qubit gate for optimisation passes.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[derive(
+    Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq, EnumString, derive_more::Display,
+)]
 pub enum TargetTwoQubitGate {
     /// Controlled-NOT
+    #[default]
     CX,
     /// TK2 gate.
     TK2,
@@ -503,7 +1109,7 @@ pub enum TargetTwoQubitGate {
 
 /// Preferred CX configuration for gadget construction.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, EnumString, derive_more::Display)]
 pub enum CxConfig {
     /// Snake configuration.
     Snake,
@@ -519,7 +1125,7 @@ pub enum CxConfig {
 
 /// Strategy for synthesising Pauli gadgets.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, EnumString, derive_more::Display)]
 pub enum PauliSynthStrategy {
     /// Synthesise gadgets individually.
     Individual,
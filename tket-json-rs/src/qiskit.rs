@@ -0,0 +1,256 @@
+//! Conversion between serialized circuits and a Qiskit-style dictionary
+//! representation.
+//!
+//! The dictionary mirrors the structure of a Qiskit `QuantumCircuit`: a flat
+//! list of named instructions acting on qubit and classical bit indices,
+//! with angles in radians. Only the gates with a direct Qiskit equivalent
+//! (see [`OpType::to_qiskit_name`]) and numeric parameters are supported.
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::circuit_json::{Command, Operation, SerialCircuit};
+use crate::optype::OpType;
+use crate::param::{self, NonFiniteError};
+use crate::register::{ElementId, Qubit};
+
+/// A circuit in the Qiskit dictionary representation.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct QiskitCircuit {
+    /// The name of the circuit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The number of qubits in the circuit.
+    pub num_qubits: usize,
+    /// The number of classical bits in the circuit.
+    pub num_clbits: usize,
+    /// The global phase, in radians.
+    #[serde(default)]
+    pub global_phase: f64,
+    /// The instructions of the circuit, in order.
+    pub instructions: Vec<QiskitInstruction>,
+}
+
+/// An instruction in a [`QiskitCircuit`].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct QiskitInstruction {
+    /// The Qiskit name of the gate.
+    pub name: String,
+    /// The indices of the qubits the gate acts on.
+    pub qubits: Vec<usize>,
+    /// The indices of the classical bits the gate acts on.
+    #[serde(default)]
+    pub clbits: Vec<usize>,
+    /// The parameters of the gate, in radians.
+    #[serde(default)]
+    pub params: Vec<f64>,
+}
+
+/// Error returned when converting to or from a [`QiskitCircuit`].
+#[derive(Clone, Debug, PartialEq, Display, Error)]
+#[non_exhaustive]
+pub enum QiskitError {
+    /// The operation has no Qiskit equivalent, or carries data that cannot be
+    /// represented.
+    #[display("command {index} applies an unsupported {op_type} operation")]
+    UnsupportedOperation {
+        /// The index of the offending command.
+        index: usize,
+        /// The type of the operation.
+        op_type: OpType,
+    },
+    /// A parameter is not a numeric value.
+    #[display("command {index} has the non-numeric parameter {param}")]
+    SymbolicParameter {
+        /// The index of the offending command.
+        index: usize,
+        /// The parameter expression.
+        param: String,
+    },
+    /// The global phase is not a numeric value.
+    #[display("the circuit has the non-numeric phase {phase}")]
+    SymbolicPhase {
+        /// The phase expression.
+        phase: String,
+    },
+    /// A command refers to a qubit or bit that is not declared.
+    #[display("command {index} acts on the undeclared unit {unit}")]
+    UndeclaredUnit {
+        /// The index of the offending command.
+        index: usize,
+        /// The undeclared unit.
+        unit: ElementId,
+    },
+    /// An instruction uses a gate with no TKET equivalent.
+    #[display("instruction {index} applies the unknown gate {name}")]
+    UnknownGate {
+        /// The index of the offending instruction.
+        index: usize,
+        /// The Qiskit name of the gate.
+        name: String,
+    },
+    /// A parameter or the global phase is not finite.
+    #[display("{_0}")]
+    NonFinite(NonFiniteError),
+    /// An instruction refers to a qubit or bit index outside the circuit.
+    #[display("instruction {index} acts on an out-of-range unit index")]
+    IndexOutOfRange {
+        /// The index of the offending instruction.
+        index: usize,
+    },
+}
+
+impl SerialCircuit {
+    /// Converts the circuit to the Qiskit dictionary representation.
+    ///
+    /// Qubits and bits are numbered in declaration order, and parameters are
+    /// converted from half-turns to radians.
+    pub fn to_qiskit_dict(&self) -> Result<QiskitCircuit, QiskitError> {
+        let qubits: HashMap<&ElementId, usize> = self
+            .qubits
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (&q.id, i))
+            .collect();
+        let bits: HashMap<&ElementId, usize> = self
+            .bits
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (&b.id, i))
+            .collect();
+
+        let mut instructions = Vec::with_capacity(self.commands.len());
+        for (index, command) in self.commands.iter().enumerate() {
+            let op = &command.op;
+            let unsupported = QiskitError::UnsupportedOperation {
+                index,
+                op_type: op.op_type,
+            };
+            let plain = Operation {
+                params: op.params.clone(),
+                ..Operation::from_optype(op.op_type)
+            };
+            if op != &plain {
+                return Err(unsupported);
+            }
+            let name = op.op_type.to_qiskit_name().ok_or(unsupported)?;
+
+            let mut instruction = QiskitInstruction {
+                name: name.to_string(),
+                qubits: Vec::new(),
+                clbits: Vec::new(),
+                params: Vec::new(),
+            };
+            for arg in &command.args {
+                if let Some(&i) = qubits.get(arg) {
+                    instruction.qubits.push(i);
+                } else if let Some(&i) = bits.get(arg) {
+                    instruction.clbits.push(i);
+                } else {
+                    return Err(QiskitError::UndeclaredUnit {
+                        index,
+                        unit: arg.clone(),
+                    });
+                }
+            }
+            for param in op.params() {
+                let radians = half_turns_to_radians(param)
+                    .map_err(QiskitError::NonFinite)?
+                    .ok_or_else(|| QiskitError::SymbolicParameter {
+                        index,
+                        param: param.clone(),
+                    })?;
+                instruction.params.push(radians);
+            }
+            instructions.push(instruction);
+        }
+
+        Ok(QiskitCircuit {
+            name: self.name.clone(),
+            num_qubits: self.qubits.len(),
+            num_clbits: self.bits.len(),
+            global_phase: half_turns_to_radians(&self.phase)
+                .map_err(QiskitError::NonFinite)?
+                .ok_or_else(|| QiskitError::SymbolicPhase {
+                    phase: self.phase.clone(),
+                })?,
+            instructions,
+        })
+    }
+
+    /// Converts a circuit from the Qiskit dictionary representation.
+    ///
+    /// The qubits and bits are placed in the default `q` and `c` registers,
+    /// and parameters are converted from radians to half-turns.
+    pub fn from_qiskit_dict(circuit: &QiskitCircuit) -> Result<Self, QiskitError> {
+        let qubit = |i: usize| ElementId("q".to_string(), vec![i as i64]);
+        let bit = |i: usize| ElementId("c".to_string(), vec![i as i64]);
+
+        let mut serial = SerialCircuit::new(
+            circuit.name.clone(),
+            param::format_number(circuit.global_phase / PI).map_err(QiskitError::NonFinite)?,
+        );
+        serial.qubits = (0..circuit.num_qubits)
+            .map(|i| Qubit::from(qubit(i)))
+            .collect();
+        serial.bits = (0..circuit.num_clbits).map(|i| bit(i).into()).collect();
+
+        for (index, instruction) in circuit.instructions.iter().enumerate() {
+            let op_type = OpType::from_qiskit_name(&instruction.name).ok_or_else(|| {
+                QiskitError::UnknownGate {
+                    index,
+                    name: instruction.name.clone(),
+                }
+            })?;
+            let in_range = instruction.qubits.iter().all(|&i| i < circuit.num_qubits)
+                && instruction.clbits.iter().all(|&i| i < circuit.num_clbits);
+            if !in_range {
+                return Err(QiskitError::IndexOutOfRange { index });
+            }
+
+            let mut op = Operation::from_optype(op_type);
+            if !instruction.params.is_empty() {
+                op.params = Some(
+                    instruction
+                        .params
+                        .iter()
+                        .map(|radians| param::format_number(radians / PI))
+                        .collect::<Result<_, _>>()
+                        .map_err(QiskitError::NonFinite)?,
+                );
+            }
+            let args = instruction
+                .qubits
+                .iter()
+                .map(|&i| qubit(i))
+                .chain(instruction.clbits.iter().map(|&i| bit(i)))
+                .collect();
+            serial.commands.push(Command {
+                op,
+                args,
+                opgroup: None,
+            });
+        }
+        Ok(serial)
+    }
+}
+
+/// Converts a numeric parameter in half-turns to radians, returning `None`
+/// for symbolic expressions.
+///
+/// Returns an error if the angle in radians is not finite, which includes
+/// literals such as `inf` and `NaN` as well as values that overflow.
+fn half_turns_to_radians(param: &str) -> Result<Option<f64>, NonFiniteError> {
+    let Ok(half_turns) = param.trim().parse::<f64>() else {
+        return Ok(None);
+    };
+    let radians = half_turns * PI;
+    if radians.is_finite() {
+        Ok(Some(radians))
+    } else {
+        Err(NonFiniteError { value: radians })
+    }
+}
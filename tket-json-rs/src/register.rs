@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 /// The first element is the name of the register, and the second element is a
 /// multi-dimensional index into the register.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Display, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Display, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[display("{_0}[{}]", _1.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", "))]
 pub struct ElementId(pub String, pub Vec<i64>);
 
@@ -20,7 +20,9 @@ pub struct ElementId(pub String, pub Vec<i64>);
 ///
 /// See [`ElementId`] for the concrete generic index.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Display, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash, From)]
+#[derive(
+    Display, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, From,
+)]
 #[display("{id}")]
 #[serde(transparent)]
 pub struct Qubit {
@@ -32,7 +34,9 @@ pub struct Qubit {
 ///
 /// See [`ElementId`] for the concrete generic index.
 #[cfg_attr(feature = "schemars", derive(JsonSchema))]
-#[derive(Display, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash, From)]
+#[derive(
+    Display, Deserialize, Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, From,
+)]
 #[display("{id}")]
 #[serde(transparent)]
 pub struct Bit {
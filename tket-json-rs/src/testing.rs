@@ -0,0 +1,155 @@
+//! [`proptest`](mod@proptest) strategies for generating serialized circuits.
+//!
+//! These are intended for downstream crates that want to check that their
+//! circuit transformations preserve the serialization invariants.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use tket_json_rs::testing::{arb_circuit, assert_json_roundtrip, GateSpec};
+//!
+//! proptest!(|(circ in arb_circuit(GateSpec::default_gate_set(), 3, 10))| {
+//!     assert_json_roundtrip(&circ);
+//! });
+//! ```
+
+use std::fmt::Debug;
+
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::circuit_json::{Command, Operation, SerialCircuit};
+use crate::optype::OpType;
+use crate::register::ElementId;
+
+/// Symbols used in generated parameter expressions.
+const SYMBOLS: &[&str] = &["a", "b", "theta"];
+
+/// A gate that may appear in generated circuits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GateSpec {
+    /// The operation type of the gate.
+    pub op_type: OpType,
+    /// The number of qubits the gate acts on.
+    pub n_qubits: usize,
+    /// The number of parameters of the gate.
+    pub n_params: usize,
+}
+
+impl GateSpec {
+    /// Create a new gate specification.
+    pub fn new(op_type: OpType, n_qubits: usize, n_params: usize) -> Self {
+        Self {
+            op_type,
+            n_qubits,
+            n_params,
+        }
+    }
+
+    /// A small universal gate set, with rotations and two-qubit gates.
+    pub fn default_gate_set() -> Vec<GateSpec> {
+        vec![
+            GateSpec::new(OpType::H, 1, 0),
+            GateSpec::new(OpType::X, 1, 0),
+            GateSpec::new(OpType::T, 1, 0),
+            GateSpec::new(OpType::Rz, 1, 1),
+            GateSpec::new(OpType::Rx, 1, 1),
+            GateSpec::new(OpType::TK1, 1, 3),
+            GateSpec::new(OpType::CX, 2, 0),
+            GateSpec::new(OpType::CZ, 2, 0),
+            GateSpec::new(OpType::ZZPhase, 2, 1),
+        ]
+    }
+}
+
+/// A strategy for parameter expressions, either a multiple of 1/8 or a
+/// symbol.
+pub fn arb_param() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => (-16i32..=16).prop_map(|n| (n as f64 / 8.0).to_string()),
+        1 => proptest::sample::select(SYMBOLS).prop_map(String::from),
+    ]
+}
+
+/// A strategy generating circuits on up to `max_qubits` qubits of a default
+/// register, with up to `max_commands` gates drawn from `gate_set`.
+///
+/// Gates acting on more qubits than the circuit has are never generated, and
+/// each command acts on distinct qubits.
+///
+/// # Panics
+///
+/// If `gate_set` is empty or `max_qubits` is zero.
+pub fn arb_circuit(
+    gate_set: Vec<GateSpec>,
+    max_qubits: usize,
+    max_commands: usize,
+) -> impl Strategy<Value = SerialCircuit> {
+    assert!(!gate_set.is_empty(), "The gate set must not be empty");
+    assert!(max_qubits > 0, "Circuits must have at least one qubit");
+    let min_qubits = gate_set.iter().map(|g| g.n_qubits).min().unwrap().max(1);
+    let max_qubits = max_qubits.max(min_qubits);
+    (min_qubits..=max_qubits)
+        .prop_flat_map(move |n_qubits| {
+            let gates: Vec<GateSpec> = gate_set
+                .iter()
+                .filter(|g| g.n_qubits <= n_qubits)
+                .copied()
+                .collect();
+            let command = proptest::sample::select(gates)
+                .prop_flat_map(move |gate| arb_command(gate, n_qubits));
+            (
+                Just(n_qubits),
+                arb_param(),
+                proptest::collection::vec(command, 0..=max_commands),
+            )
+        })
+        .prop_map(|(n_qubits, phase, commands)| {
+            let mut circuit = SerialCircuit::new(None, phase);
+            circuit.qubits = (0..n_qubits).map(|i| default_qubit(i).into()).collect();
+            circuit.commands = commands;
+            circuit
+        })
+}
+
+/// A strategy generating a command applying `gate` to distinct qubits of a
+/// default register with `n_qubits` elements.
+fn arb_command(gate: GateSpec, n_qubits: usize) -> impl Strategy<Value = Command> {
+    (
+        Just((0..n_qubits).collect::<Vec<_>>()).prop_shuffle(),
+        proptest::collection::vec(arb_param(), gate.n_params),
+    )
+        .prop_map(move |(qubits, params)| {
+            let mut op = Operation::from_optype(gate.op_type);
+            if gate.n_params > 0 {
+                op.params = Some(params);
+            }
+            Command {
+                op,
+                args: qubits[..gate.n_qubits]
+                    .iter()
+                    .map(|&i| default_qubit(i))
+                    .collect(),
+                opgroup: None,
+            }
+        })
+}
+
+/// The `index`-th qubit of the default register.
+fn default_qubit(index: usize) -> ElementId {
+    ElementId("q".to_string(), vec![index as i64])
+}
+
+/// Asserts that `value` is unchanged by a roundtrip through JSON.
+///
+/// # Panics
+///
+/// If serialization fails, or the decoded value differs from `value`.
+pub fn assert_json_roundtrip<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    let decoded: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(&decoded, value, "JSON roundtrip changed the value: {json}");
+}
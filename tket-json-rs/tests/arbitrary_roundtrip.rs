@@ -0,0 +1,62 @@
+//! Roundtrip property tests over arbitrary-generated values.
+#![cfg(feature = "arbitrary")]
+
+use std::fmt::Debug;
+
+use arbitrary::{Arbitrary, Unstructured};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tket_json_rs::circuit_json::Command;
+use tket_json_rs::pass::BasePass;
+use tket_json_rs::{OpType, SerialCircuit};
+
+/// Number of random inputs tried for each type.
+const N_SAMPLES: u64 = 256;
+
+/// Deterministic pseudo-random input bytes for the given seed.
+fn random_bytes(seed: u64) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    (0..1024)
+        .map(|_| {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+/// Check that `from_json(to_json(x)) == x` for generated values of `T`.
+fn check_roundtrip<T>()
+where
+    T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    for seed in 0..N_SAMPLES {
+        let bytes = random_bytes(seed);
+        let value: T = Unstructured::new(&bytes).arbitrary().unwrap();
+        let json = serde_json::to_string(&value).unwrap();
+        let decoded: T = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, value, "Roundtrip failed for {json}");
+    }
+}
+
+#[test]
+fn optype_roundtrip() {
+    check_roundtrip::<OpType>();
+}
+
+#[test]
+fn command_roundtrip() {
+    check_roundtrip::<Command>();
+}
+
+#[test]
+fn circuit_roundtrip() {
+    check_roundtrip::<SerialCircuit>();
+}
+
+#[test]
+fn pass_roundtrip() {
+    check_roundtrip::<BasePass>();
+}
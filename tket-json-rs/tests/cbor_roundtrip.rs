@@ -0,0 +1,37 @@
+//! Roundtrip tests for the CBOR encoding.
+#![cfg(feature = "cbor")]
+
+use rstest::rstest;
+use tket_json_rs::SerialCircuit;
+
+const SIMPLE: &str = include_str!("data/circuit/simple.json");
+const CLASSICAL: &str = include_str!("data/circuit/classical.json");
+const DIAGONAL: &str = include_str!("data/circuit/diagonal-box.json");
+const WASM: &str = include_str!("data/circuit/wasm.json");
+
+#[rstest]
+#[case::simple(SIMPLE)]
+#[case::classical(CLASSICAL)]
+#[case::diagonal_box(DIAGONAL)]
+#[case::wasm(WASM)]
+fn cbor_roundtrip(#[case] json: &str) {
+    let circuit: SerialCircuit = serde_json::from_str(json).unwrap();
+
+    let cbor = circuit.to_cbor().unwrap();
+    let decoded = SerialCircuit::from_cbor(&cbor).unwrap();
+    assert_eq!(decoded, circuit);
+
+    // The binary encoding is more compact than the minified JSON.
+    let minified = serde_json::to_vec(&circuit).unwrap();
+    assert!(
+        cbor.len() < minified.len(),
+        "CBOR encoding ({} bytes) is larger than JSON ({} bytes)",
+        cbor.len(),
+        minified.len()
+    );
+}
+
+#[test]
+fn invalid_cbor() {
+    assert!(SerialCircuit::from_cbor(&[0xff, 0x00]).is_err());
+}
@@ -0,0 +1,629 @@
+//! Tests for the read-only circuit queries.
+use std::collections::{BTreeSet, HashMap};
+
+use rstest::rstest;
+use serde_json::{json, Value};
+use tket_json_rs::circuit_json::stim::StimError;
+use tket_json_rs::register::ElementId;
+use tket_json_rs::{OpType, SerialCircuit};
+
+/// A two-qubit circuit mixing plain gates, a box, and a conditional.
+fn mixed_circuit() -> SerialCircuit {
+    serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]]], "op": {"type": "Rz", "params": ["0.5"]}},
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {
+                "args": [["q", [0]]],
+                "op": {
+                    "type": "CircBox",
+                    "box": {
+                        "type": "CircBox",
+                        "id": "7d5c4b3a-2f1e-4d0c-9b8a-6e5d4c3b2a10",
+                        "circuit": {
+                            "bits": [],
+                            "commands": [{"args": [["q", [0]]], "op": {"type": "T"}}],
+                            "implicit_permutation": [],
+                            "phase": "0",
+                            "qubits": [["q", [0]]]
+                        }
+                    }
+                }
+            },
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {
+                "args": [["c", [0]], ["q", [1]]],
+                "op": {
+                    "type": "Conditional",
+                    "conditional": {"op": {"type": "X"}, "width": 1, "value": 1}
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn gate_set() {
+    let circuit = mixed_circuit();
+
+    assert_eq!(
+        circuit.gate_set(),
+        BTreeSet::from([
+            OpType::H,
+            OpType::CX,
+            OpType::Rz,
+            OpType::CircBox,
+            OpType::Measure,
+            OpType::Conditional,
+        ])
+    );
+    assert_eq!(
+        circuit.gate_set_recursive(),
+        BTreeSet::from([
+            OpType::H,
+            OpType::CX,
+            OpType::Rz,
+            OpType::CircBox,
+            OpType::T,
+            OpType::Measure,
+            OpType::Conditional,
+            OpType::X,
+        ])
+    );
+}
+
+#[test]
+fn free_symbols() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["2*a + sin(pi*b)"]}},
+            {"args": [["q", [0]]], "op": {"type": "Rx", "params": ["1.5e-3"]}},
+            {
+                "args": [["q", [0]]],
+                "op": {
+                    "type": "CircBox",
+                    "box": {
+                        "type": "CircBox",
+                        "id": "0b1c2d3e-4f50-4617-8293-a4b5c6d7e8f9",
+                        "circuit": {
+                            "bits": [],
+                            "commands": [
+                                {"args": [["q", [0]]], "op": {"type": "Ry", "params": ["-c"]}}
+                            ],
+                            "implicit_permutation": [],
+                            "phase": "theta_1/2",
+                            "qubits": [["q", [0]]]
+                        }
+                    }
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "a",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+
+    assert_eq!(
+        circuit.free_symbols(),
+        BTreeSet::from(["a", "b", "c", "theta_1"].map(String::from))
+    );
+    assert!(mixed_circuit().free_symbols().is_empty());
+}
+
+#[test]
+fn interaction_graph() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]], ["q", [0]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [2]], ["q", [1]]], "op": {"type": "CZ"}},
+            {"args": [["q", [0]], ["q", [1]], ["q", [2]]], "op": {"type": "CCX"}},
+            {"args": [["q", [2]], ["c", [0]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    }))
+    .unwrap();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+
+    assert_eq!(
+        circuit.interaction_graph(),
+        vec![(q(0), q(1), 3), (q(1), q(2), 1)]
+    );
+    assert_eq!(mixed_circuit().interaction_graph(), vec![(q(0), q(1), 1)]);
+}
+
+#[test]
+fn qubit_lifecycle() {
+    let initial_json = json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [1]]], "op": {"type": "Create"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]]], "op": {"type": "Discard"}}
+        ],
+        "created_qubits": [["q", [0]], ["q", [1]]],
+        "discarded_qubits": [],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    });
+    let circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&circuit).unwrap(), initial_json);
+
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    assert_eq!(circuit.created_qubits(), vec![&q(0), &q(1)]);
+    assert_eq!(circuit.discarded_qubits(), vec![&q(0)]);
+    assert!(mixed_circuit().created_qubits().is_empty());
+}
+
+#[test]
+fn to_dot() {
+    let dot = mixed_circuit().to_dot();
+    assert!(dot.starts_with("digraph {"));
+
+    let nodes = dot
+        .lines()
+        .filter(|l| l.contains("[label=") && !l.contains("->"));
+    assert_eq!(nodes.count(), mixed_circuit().commands.len());
+    assert!(dot.contains(r#"1 [label="CX(q[0], q[1])"];"#));
+
+    // Wire dependencies: q[0] links H, CX, H, CircBox, Measure; q[1] links
+    // CX, Rz, Conditional; c[0] links Measure and Conditional.
+    let edges = dot.lines().filter(|l| l.contains("->"));
+    assert_eq!(edges.count(), 4 + 2 + 1);
+    assert!(dot.contains(r#"5 -> 6 [label="c[0]"];"#));
+}
+
+#[test]
+fn measurement_map() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]], ["c", [0]]], "op": {"type": "Measure"}},
+            {"args": [["q", [0]], ["c", [1]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    let c = |i| ElementId("c".to_string(), vec![i]);
+
+    assert_eq!(circuit.n_measurements(), 2);
+    assert_eq!(circuit.measurement_map(), vec![(q(1), c(0)), (q(0), c(1))]);
+}
+
+#[test]
+fn to_quantikz() {
+    let quantikz = mixed_circuit().to_quantikz();
+    let lines: Vec<&str> = quantikz.lines().collect();
+    assert_eq!(lines.first(), Some(&r"\begin{quantikz}"));
+    assert_eq!(lines.last(), Some(&r"\end{quantikz}"));
+
+    // One row per qubit and bit, with one column per layer and a final wire.
+    assert_eq!(lines.len(), 2 + 3);
+    assert_eq!(
+        lines[1],
+        r"\lstick{q[0]} & \gate{H} & \ctrl{1} & \gate{H} & \gate{CircBox} & \meter{} & \qw & \qw \\"
+    );
+    assert_eq!(
+        lines[2],
+        r"\lstick{q[1]} & \qw & \targ{} & \gate{Rz(0.5)} & \qw & \qw & \gate{Conditional} & \qw \\"
+    );
+    assert_eq!(
+        lines[3],
+        r"\lstick{c[0]} & \cw & \cw & \cw & \cw & \cw & \cw & \cw"
+    );
+}
+
+#[test]
+fn condition_bits() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]], ["c", [2]]],
+        "commands": [
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {
+                "args": [["c", [0]], ["q", [1]]],
+                "op": {
+                    "type": "Conditional",
+                    "conditional": {"op": {"type": "X"}, "width": 1, "value": 1}
+                }
+            },
+            {"args": [["q", [1]], ["c", [1]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+
+    let c = |i| ElementId("c".to_string(), vec![i]);
+    assert_eq!(circuit.condition_bits(), BTreeSet::from([c(0)]));
+    assert_eq!(mixed_circuit().condition_bits(), BTreeSet::from([c(0)]));
+}
+
+#[test]
+fn classical_predicates() {
+    let quantum: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    assert!(quantum.is_purely_quantum());
+    assert!(!quantum.has_classical_control());
+
+    // Measurement followed by a conditional X.
+    let feed_forward = mixed_circuit();
+    assert!(!feed_forward.is_purely_quantum());
+    assert!(feed_forward.has_classical_control());
+
+    let mut measured = quantum.clone();
+    measured.commands.push(
+        serde_json::from_value(
+            json!({"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}),
+        )
+        .unwrap(),
+    );
+    assert!(!measured.is_purely_quantum());
+    assert!(!measured.has_classical_control());
+}
+
+#[rstest]
+#[case::rng(json!({"args": [["c", [0]]], "op": {"type": "RNGSeed"}}))]
+#[case::shot_num(json!({"args": [["c", [0]]], "op": {"type": "JobShotNum"}}))]
+#[case::branch(json!({"args": [["c", [0]]], "op": {"type": "Branch"}}))]
+#[case::stop(json!({"args": [], "op": {"type": "Stop"}}))]
+fn classical_ops_are_not_quantum(#[case] command: Value) {
+    let mut circuit = SerialCircuit::new(None, "0".to_string());
+    circuit
+        .commands
+        .push(serde_json::from_value(command).unwrap());
+    assert!(!circuit.is_purely_quantum());
+}
+
+#[test]
+fn labelled_barriers() {
+    let initial_json = json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {
+                "args": [["q", [0]], ["q", [1]]],
+                "op": {"type": "Barrier", "data": "custom_sync", "signature": ["Q", "Q"]}
+            },
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]]], "op": {"type": "Barrier", "signature": ["Q"]}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    });
+    let circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&circuit).unwrap(), initial_json);
+
+    assert_eq!(
+        circuit.barriers(),
+        vec![(1, Some("custom_sync")), (3, None)]
+    );
+}
+
+#[test]
+fn qubit_lifetimes() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {"args": [["q", [1]], ["q", [2]]], "op": {"type": "CZ"}},
+            {"args": [["q", [2]]], "op": {"type": "X"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]], ["q", [3]]]
+    }))
+    .unwrap();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+
+    assert_eq!(
+        circuit.qubit_lifetimes(),
+        HashMap::from([(q(0), (0, 2)), (q(1), (1, 3)), (q(2), (3, 4))])
+    );
+}
+
+#[test]
+fn unit_kinds() {
+    let circuit = mixed_circuit();
+    let q0 = ElementId("q".to_string(), vec![0]);
+    let c0 = ElementId("c".to_string(), vec![0]);
+    let undeclared = ElementId("q".to_string(), vec![5]);
+
+    assert!(circuit.is_qubit(&q0));
+    assert!(!circuit.is_bit(&q0));
+    assert!(circuit.is_bit(&c0));
+    assert!(!circuit.is_qubit(&c0));
+    assert!(!circuit.is_qubit(&undeclared));
+    assert!(!circuit.is_bit(&undeclared));
+}
+
+#[test]
+fn tensor_network() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    let network = circuit.to_tensor_network();
+
+    assert_eq!(network.tensors.len(), 2);
+    let ranks: Vec<usize> = network.tensors.iter().map(|t| t.rank()).collect();
+    assert_eq!(ranks, vec![2, 4]);
+    assert_eq!(network.n_indices, 5);
+
+    // The H output feeds the CX control, and the second qubit's input leg
+    // goes directly to the CX target.
+    let (h, cx) = (&network.tensors[0], &network.tensors[1]);
+    assert_eq!(h.inputs, vec![0]);
+    assert_eq!(cx.inputs, vec![h.outputs[0], 1]);
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    assert_eq!(
+        network.outputs,
+        vec![(q(0), cx.outputs[0]), (q(1), cx.outputs[1])]
+    );
+
+    // Every index is shared by exactly two legs.
+    let mut degree = vec![0; network.n_indices];
+    let legs = network
+        .tensors
+        .iter()
+        .flat_map(|t| t.inputs.iter().chain(&t.outputs))
+        .chain(network.inputs.iter().map(|(_, i)| i))
+        .chain(network.outputs.iter().map(|(_, i)| i));
+    for &leg in legs {
+        degree[leg] += 1;
+    }
+    assert!(degree.iter().all(|&d| d == 2));
+}
+
+#[test]
+fn t_and_rotation_counts() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "T"}},
+            {"args": [["q", [1]]], "op": {"type": "Tdg"}},
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["-0.75"]}},
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.5"]}},
+            {"args": [["q", [1]]], "op": {"type": "Rz", "params": ["0.3"]}},
+            {"args": [["q", [1]]], "op": {"type": "Rx", "params": ["a"]}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {
+                "args": [["q", [0]]],
+                "op": {
+                    "type": "CircBox",
+                    "box": {
+                        "type": "CircBox",
+                        "id": "4e3c2b1a-0f9e-4d8c-b7a6-958473625140",
+                        "circuit": {
+                            "bits": [],
+                            "commands": [{"args": [["q", [0]]], "op": {"type": "T"}}],
+                            "implicit_permutation": [],
+                            "phase": "0",
+                            "qubits": [["q", [0]]]
+                        }
+                    }
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+
+    assert_eq!(circuit.t_count(), 4);
+    assert_eq!(circuit.rotation_count(), 2);
+}
+
+#[test]
+fn equality_ignoring_opgroups() {
+    let circuit = |opgroup: Option<&str>| -> SerialCircuit {
+        let mut cx = json!({"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}});
+        if let Some(opgroup) = opgroup {
+            cx["opgroup"] = opgroup.into();
+        }
+        serde_json::from_value(json!({
+            "bits": [],
+            "commands": [{"args": [["q", [0]]], "op": {"type": "H"}}, cx],
+            "implicit_permutation": [],
+            "phase": "0",
+            "qubits": [["q", [0]], ["q", [1]]]
+        }))
+        .unwrap()
+    };
+    let (a, b, plain) = (circuit(Some("a")), circuit(Some("b")), circuit(None));
+
+    assert_ne!(a.commands[1], b.commands[1]);
+    assert!(a.commands[1].eq_ignoring_opgroup(&b.commands[1]));
+    assert!(!a.commands[0].eq_ignoring_opgroup(&a.commands[1]));
+
+    assert_ne!(a, b);
+    assert!(a.eq_ignoring_opgroups(&b));
+    assert!(a.eq_ignoring_opgroups(&plain));
+
+    let mut other_phase = plain.clone();
+    other_phase.phase = "0.5".to_string();
+    assert!(!a.eq_ignoring_opgroups(&other_phase));
+}
+
+#[test]
+fn opgroup_queries() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}, "opgroup": "prep"},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]]], "op": {"type": "Rz", "params": ["0.5"]}, "opgroup": "tunable"},
+            {"args": [["q", [0]]], "op": {"type": "H"}, "opgroup": "prep"}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+
+    assert_eq!(circuit.opgroups(), BTreeSet::from(["prep", "tunable"]));
+    assert_eq!(circuit.commands_in_opgroup("prep"), vec![0, 3]);
+    assert_eq!(circuit.commands_in_opgroup("tunable"), vec![2]);
+    assert!(circuit.commands_in_opgroup("missing").is_empty());
+}
+
+#[test]
+fn to_stim() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "Reset"}},
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "Barrier"}},
+            {"args": [["q", [1]]], "op": {"type": "S"}},
+            {"args": [["q", [1]], ["q", [0]]], "op": {"type": "CZ"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {"args": [["q", [1]], ["c", [1]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    assert_eq!(
+        circuit.to_stim().unwrap(),
+        "R 0\nH 0\nCX 0 1\nS 1\nCZ 1 0\nM 0\nM 1\n"
+    );
+
+    assert_eq!(
+        mixed_circuit().to_stim(),
+        Err(StimError::UnsupportedOperation {
+            index: 2,
+            op_type: OpType::Rz
+        })
+    );
+
+    // Commands with too few or too many arguments are not written.
+    let mut short = circuit.clone();
+    short.commands[2].args.pop();
+    assert_eq!(
+        short.to_stim(),
+        Err(StimError::ArityMismatch {
+            index: 2,
+            expected: 2,
+            found: 1
+        })
+    );
+    let mut long = circuit.clone();
+    long.commands[1]
+        .args
+        .push(ElementId("q".to_string(), vec![1]));
+    assert_eq!(
+        long.to_stim(),
+        Err(StimError::ArityMismatch {
+            index: 1,
+            expected: 1,
+            found: 2
+        })
+    );
+}
+
+#[test]
+fn terminal_measurements() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]], ["c", [1]]], "op": {"type": "Measure"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "Barrier"}},
+            {"args": [["q", [0]], ["c", [1]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    // The first measurement is followed by a CX on its qubit.
+    assert_eq!(circuit.terminal_measurements(), vec![2, 4]);
+}
+
+#[test]
+fn qubit_depths() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]]], "op": {"type": "T"}},
+            {"args": [["q", [0]], ["q", [1]], ["q", [2]]], "op": {"type": "Barrier"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    }))
+    .unwrap();
+    let q = |i: i64| ElementId("q".to_string(), vec![i]);
+    assert_eq!(
+        circuit.qubit_depths(),
+        HashMap::from([(q(0), 4), (q(1), 1), (q(2), 0)])
+    );
+}
+
+#[test]
+fn control_counts() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]], ["q", [1]], ["q", [2]], ["q", [3]]], "op": {"type": "CnX"}},
+            {"args": [["q", [0]], ["q", [1]], ["q", [3]]], "op": {"type": "CnRy", "params": ["0.5"]}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]]], "op": {"type": "H"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]], ["q", [3]]]
+    }))
+    .unwrap();
+    assert_eq!(circuit.control_count_of(0), Some(3));
+    assert_eq!(circuit.control_count_of(1), Some(2));
+    assert_eq!(circuit.control_count_of(2), Some(1));
+    assert_eq!(circuit.control_count_of(3), None);
+    assert_eq!(circuit.control_count_of(4), None);
+}
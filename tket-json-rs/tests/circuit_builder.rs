@@ -0,0 +1,42 @@
+//! Tests for the circuit builder.
+use serde_json::json;
+use tket_json_rs::circuit_json::{CircuitBuilder, Operation};
+use tket_json_rs::register::ElementId;
+use tket_json_rs::{OpType, SerialCircuit};
+
+#[test]
+fn measured_bell_circuit() {
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    let c = |i| ElementId("c".to_string(), vec![i]);
+
+    let mut builder = CircuitBuilder::new();
+    builder
+        .name("bell")
+        .add_qubit_register("q", 2)
+        .add_bit_register("c", 2)
+        .apply(Operation::from_optype(OpType::H), &[q(0)])
+        .apply(Operation::from_optype(OpType::CX), &[q(0), q(1)])
+        .measure(q(0), c(0))
+        .measure(q(1), c(1));
+    let circuit = builder.finish();
+
+    let expected_json = json!({
+        "name": "bell",
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {"args": [["q", [1]], ["c", [1]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    });
+    assert_eq!(serde_json::to_value(&circuit).unwrap(), expected_json);
+    assert_eq!(
+        serde_json::from_value::<SerialCircuit>(expected_json).unwrap(),
+        circuit
+    );
+    assert_eq!(circuit.validate(), Ok(()));
+}
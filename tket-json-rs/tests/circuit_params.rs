@@ -0,0 +1,257 @@
+//! Tests for the handling of operation parameters.
+use assert_json_diff::assert_json_eq;
+use rstest::rstest;
+use serde_json::{json, Value};
+use tket_json_rs::{param, SerialCircuit};
+
+/// Doubles a numeric parameter, leaving symbolic ones untouched.
+fn double(param: &str) -> String {
+    match param.parse::<f64>() {
+        Ok(value) => (2.0 * value).to_string(),
+        Err(_) => param.to_string(),
+    }
+}
+
+#[test]
+fn map_params_in_place_doubles_rotations() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.25"]}},
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["a"]}},
+            {
+                "args": [["q", [0]]],
+                "op": {
+                    "type": "CircBox",
+                    "box": {
+                        "type": "CircBox",
+                        "id": "2f5bd3a1-7a83-4c38-9d1e-8d2f0c9e6b11",
+                        "circuit": {
+                            "bits": [],
+                            "commands": [
+                                {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.5"]}}
+                            ],
+                            "implicit_permutation": [],
+                            "phase": "0.125",
+                            "qubits": [["q", [0]]]
+                        }
+                    }
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+
+    circuit.map_params_in_place(double);
+
+    assert_json_eq!(
+        serde_json::to_value(&circuit).unwrap(),
+        json!({
+            "bits": [],
+            "commands": [
+                {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.5"]}},
+                {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["a"]}},
+                {
+                    "args": [["q", [0]]],
+                    "op": {
+                        "type": "CircBox",
+                        "box": {
+                            "type": "CircBox",
+                            "id": "2f5bd3a1-7a83-4c38-9d1e-8d2f0c9e6b11",
+                            "circuit": {
+                                "bits": [],
+                                "commands": [
+                                    {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["1"]}}
+                                ],
+                                "implicit_permutation": [],
+                                "phase": "0.25",
+                                "qubits": [["q", [0]]]
+                            }
+                        }
+                    }
+                }
+            ],
+            "implicit_permutation": [],
+            "phase": "0",
+            "qubits": [["q", [0]]]
+        })
+    );
+}
+
+#[test]
+fn map_params_in_place_updates_box_phases() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {
+                "args": [["q", [0]], ["q", [1]]],
+                "op": {
+                    "type": "PauliExpBox",
+                    "box": {
+                        "type": "PauliExpBox",
+                        "id": "0d9f8e7c-6b5a-4c3d-9e2f-1a0b9c8d7e6f",
+                        "paulis": ["X", "Z"],
+                        "phase": "0.25",
+                        "cx_config": "Tree"
+                    }
+                }
+            },
+            {
+                "args": [["q", [0]], ["q", [1]]],
+                "op": {
+                    "type": "PhasePolyBox",
+                    "box": {
+                        "type": "PhasePolyBox",
+                        "id": "5e4d3c2b-1a09-4f8e-b7d6-c5b4a3928170",
+                        "n_qubits": 2,
+                        "qubit_indices": [[["q", [0]], 0], [["q", [1]], 1]],
+                        "phase_polynomial": [[[true, true], "0.5"], [[true, false], "b"]],
+                        "linear_transformation": [[true, false], [false, true]]
+                    }
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    assert_eq!(circuit.free_symbols(), ["b".to_string()].into());
+
+    circuit.map_params_in_place(|p| double(&p.replace('b', "a")));
+
+    let value = serde_json::to_value(&circuit).unwrap();
+    assert_eq!(value["commands"][0]["op"]["box"]["phase"], "0.5");
+    assert_json_eq!(
+        value["commands"][1]["op"]["box"]["phase_polynomial"],
+        json!([[[true, true], "1"], [[true, false], "a"]])
+    );
+    assert_eq!(circuit.free_symbols(), ["a".to_string()].into());
+}
+
+#[test]
+fn operation_params() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.5"]}},
+            {"args": [["q", [0]]], "op": {"type": "H"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+    let (rz, h) = (&circuit.commands[0].op, &circuit.commands[1].op);
+
+    assert!(rz.op_type.is_parametrized());
+    assert_eq!(rz.params(), ["0.5"]);
+    assert!(!h.op_type.is_parametrized());
+    assert!(h.params().is_empty());
+}
+
+#[rstest]
+#[case::string(json!("0.5"), "0.5")]
+#[case::float(json!(0.5), "0.5")]
+#[case::integer(json!(1), "1")]
+#[case::negative(json!(-0.25), "-0.25")]
+fn numeric_params(#[case] param: Value, #[case] expected: &str) {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{"args": [["q", [0]]], "op": {"type": "Rz", "params": [param]}}],
+        "implicit_permutation": [],
+        "phase": param,
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+
+    assert_eq!(circuit.phase, expected);
+    assert_eq!(circuit.commands[0].op.params(), [expected]);
+}
+
+/// Deserializes a single-command circuit with the given rotation parameter.
+fn rotation_circuit(param: Value) -> Result<SerialCircuit, serde_json::Error> {
+    serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{"args": [["q", [0]]], "op": {"type": "Rz", "params": [param]}}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+}
+
+#[rstest]
+#[case::finite(json!("0.5"), true)]
+#[case::symbolic_infinity(json!("oo"), true)]
+#[case::symbol(json!("inf_angle"), true)]
+#[case::infinity(json!("inf"), false)]
+#[case::negative_infinity(json!("-Infinity"), false)]
+#[case::overflow(json!("1e999"), false)]
+#[case::nan(json!("NaN"), false)]
+fn non_finite_params(#[case] param: Value, #[case] accepted: bool) {
+    let result = rotation_circuit(param);
+    assert_eq!(result.is_ok(), accepted);
+    if let Err(e) = result {
+        assert!(e.to_string().contains("non-finite parameter value"));
+    }
+}
+
+#[test]
+fn format_number() {
+    assert_eq!(param::format_number(-0.25), Ok("-0.25".to_string()));
+    assert_eq!(param::format_number(2.0), Ok("2".to_string()));
+    assert!(param::format_number(f64::INFINITY).is_err());
+    assert!(param::format_number(f64::NAN).is_err());
+}
+
+#[rstest]
+#[case::symbolic("pi/2")]
+#[case::trailing_zero("0.50")]
+#[case::exponent("1e-3")]
+#[case::spacing("2 * a")]
+fn params_are_kept_verbatim(#[case] expr: &str) {
+    let initial_json = json!({
+        "bits": [],
+        "commands": [{"args": [["q", [0]]], "op": {"type": "Rz", "params": [expr]}}],
+        "implicit_permutation": [],
+        "phase": expr,
+        "qubits": [["q", [0]]]
+    });
+    let circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+    assert_eq!(circuit.phase, expr);
+
+    let reencoded = serde_json::to_string(&circuit).unwrap();
+    assert!(reencoded.contains(&format!(r#""phase":"{expr}""#)));
+    assert_json_eq!(
+        serde_json::from_str::<Value>(&reencoded).unwrap(),
+        initial_json
+    );
+}
+
+/// Parameter types accepting numbers receive them unchanged.
+#[test]
+fn numeric_param_types() {
+    let circuit: SerialCircuit<f64> = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{"args": [["q", [0]]], "op": {"type": "Rz", "params": [0.5]}}],
+        "implicit_permutation": [],
+        "phase": 1,
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+    assert_eq!(circuit.phase, 1.0);
+    assert_eq!(circuit.commands[0].op.params(), [0.5]);
+
+    // Strings are still passed as strings.
+    let result = serde_json::from_value::<SerialCircuit<f64>>(json!({
+        "bits": [],
+        "commands": [],
+        "implicit_permutation": [],
+        "phase": "0.5",
+        "qubits": []
+    }));
+    assert!(result.is_err());
+}
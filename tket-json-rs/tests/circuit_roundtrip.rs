@@ -1,9 +1,13 @@
 //! Roundtrip tests
+use std::collections::HashMap;
+
 use assert_json_diff::assert_json_eq;
 use rstest::rstest;
 use serde_json::{json, Value};
+use tket_json_rs::circuit_json::{Classical, Operation, SchemaVersionCheck};
+use tket_json_rs::opbox::OpBox;
 use tket_json_rs::register::ElementId;
-use tket_json_rs::SerialCircuit;
+use tket_json_rs::{OpType, SerialCircuit};
 
 const SIMPLE: &str = include_str!("data/circuit/simple.json");
 const CLASSICAL: &str = include_str!("data/circuit/classical.json");
@@ -99,3 +103,295 @@ fn commands_with_no_args() {
         vec![ElementId("q".to_string(), vec![0])]
     );
 }
+
+/// Older pytket releases named custom gates `Composite`.
+#[rstest]
+fn legacy_composite_gate() {
+    let circuit_json = json!({
+        "bits": [],
+        "commands": [
+            {
+                "args": [["q", [0]]],
+                "op": {
+                    "type": "Composite",
+                    "box": {
+                        "type": "Composite",
+                        "id": "8f9e3c0e-2a1b-4f7e-9c6d-3b2a1f0e9d8c",
+                        "gate": {
+                            "name": "my_gate",
+                            "args": [],
+                            "definition": {
+                                "bits": [],
+                                "commands": [],
+                                "implicit_permutation": [],
+                                "phase": "0",
+                                "qubits": [["q", [0]]]
+                            }
+                        },
+                        "params": []
+                    }
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    });
+
+    let circuit: SerialCircuit = serde_json::from_value(circuit_json).unwrap();
+    let op = &circuit.commands[0].op;
+    assert_eq!(op.op_type, OpType::CustomGate);
+    assert!(matches!(op.op_box, Some(OpBox::CustomGate { .. })));
+
+    let reencoded_json = serde_json::to_value(&circuit).unwrap();
+    assert_eq!(reencoded_json["commands"][0]["op"]["type"], "CustomGate");
+    assert_eq!(
+        reencoded_json["commands"][0]["op"]["box"]["type"],
+        "CustomGate"
+    );
+}
+
+/// Wraps a single operation acting on two bits into a circuit.
+fn two_bit_circuit(op: Value) -> Value {
+    json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [{"args": [["c", [0]], ["c", [1]]], "op": op}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": []
+    })
+}
+
+fn set_bits_op(values: Vec<bool>) -> Operation {
+    let mut op = Operation::from_optype(OpType::SetBits);
+    op.classical = Some(Box::new(Classical::SetBits { values }));
+    op
+}
+
+#[rstest]
+#[case::set_bits(
+    json!({"type": "SetBits", "classical": {"values": [true, false]}}),
+    Classical::SetBits { values: vec![true, false] },
+)]
+#[case::copy_bits(
+    json!({"type": "CopyBits", "classical": {"n_i": 1}}),
+    Classical::CopyBits { n_i: 1 },
+)]
+#[case::range_predicate(
+    json!({"type": "RangePredicate", "classical": {"n_i": 1, "lower": 0, "upper": 1}}),
+    Classical::RangePredicate { n_i: 1, lower: 0, upper: 1 },
+)]
+#[case::explicit_predicate(
+    json!({
+        "type": "ExplicitPredicate",
+        "classical": {"n_i": 1, "name": "NOT", "values": [true, false]}
+    }),
+    Classical::Explicit { n_i: 1, name: "NOT".to_string(), values: vec![true, false] },
+)]
+#[case::multi_bit(
+    json!({
+        "type": "MultiBit",
+        "classical": {
+            "n": 2,
+            "op": {"type": "SetBits", "classical": {"values": [true]}}
+        }
+    }),
+    Classical::MultiBit {
+        n: 2,
+        op: Box::new(set_bits_op(vec![true])),
+    },
+)]
+fn classical_op_roundtrip(#[case] op: Value, #[case] expected: Classical) {
+    let initial_json = two_bit_circuit(op);
+    let circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+
+    let classical = circuit.commands[0].op.classical.as_deref().unwrap();
+    assert_eq!(classical, &expected);
+
+    let reencoded_json = serde_json::to_value(&circuit).unwrap();
+    assert_json_eq!(reencoded_json, initial_json);
+}
+
+#[test]
+fn set_bits_values() {
+    let initial_json =
+        two_bit_circuit(json!({"type": "SetBits", "classical": {"values": [false, true]}}));
+    let circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+
+    let command = &circuit.commands[0];
+    assert_eq!(command.op.set_bits_values(), Some([false, true].as_slice()));
+    assert_eq!(command.args[1], ElementId("c".to_string(), vec![1]));
+    assert_json_eq!(serde_json::to_value(&circuit).unwrap(), initial_json);
+
+    assert_eq!(
+        Operation::<String>::from_optype(OpType::SetBits).set_bits_values(),
+        None
+    );
+    assert_eq!(
+        Operation::<String>::from_optype(OpType::H).set_bits_values(),
+        None
+    );
+}
+
+#[rstest]
+fn classical_exp_box_roundtrip() {
+    let initial_json = two_bit_circuit(json!({
+        "type": "ClassicalExpBox",
+        "box": {
+            "type": "ClassicalExpBox",
+            "id": "c4d0b1b6-1f0e-4d7c-8a55-2f36b7a90e11",
+            "n_i": 1,
+            "n_io": 0,
+            "n_o": 1,
+            "exp": {"args": [["c", [0]], 1], "op": "BitWiseOp.XOR"}
+        }
+    }));
+    let circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+
+    let Some(OpBox::ClassicalExpBox { n_i, n_o, exp, .. }) = &circuit.commands[0].op.op_box else {
+        panic!("Expected a ClassicalExpBox");
+    };
+    assert_eq!((*n_i, *n_o), (1, 1));
+    assert_eq!(exp.op, "BitWiseOp.XOR");
+    assert_eq!(exp.args.len(), 2);
+
+    let reencoded_json = serde_json::to_value(&circuit).unwrap();
+    assert_json_eq!(reencoded_json, initial_json);
+}
+
+#[rstest]
+#[case::unversioned(None, SchemaVersionCheck::Unversioned)]
+#[case::supported(Some("circuit_v1"), SchemaVersionCheck::Supported)]
+#[case::unsupported(
+    Some("circuit_v2"),
+    SchemaVersionCheck::Unsupported("circuit_v2".to_string())
+)]
+fn schema_version(#[case] version: Option<&str>, #[case] expected: SchemaVersionCheck) {
+    let mut circuit_json = json!({
+        "bits": [],
+        "commands": [],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": []
+    });
+    if let Some(version) = version {
+        circuit_json["schema_version"] = version.into();
+    }
+
+    let (circuit, check) =
+        SerialCircuit::<String>::from_json_lenient(&circuit_json.to_string()).unwrap();
+    assert_eq!(circuit.schema_version(), version);
+    assert_eq!(check, expected);
+
+    let reencoded_json = serde_json::to_value(&circuit).unwrap();
+    assert_json_eq!(reencoded_json, circuit_json);
+}
+
+#[test]
+fn implicit_permutation_default() {
+    // pytket writes the identity permutation explicitly, and it is kept.
+    let present: Value = serde_json::from_str(SIMPLE).unwrap();
+    assert_eq!(present["implicit_permutation"].as_array().unwrap().len(), 2);
+    let circuit: SerialCircuit = serde_json::from_value(present.clone()).unwrap();
+    assert_json_eq!(serde_json::to_value(&circuit).unwrap(), present);
+
+    // A missing permutation is read as the identity, and written back as an
+    // empty list.
+    let absent = json!({
+        "bits": [],
+        "commands": [{"args": [["q", [0]]], "op": {"type": "H"}}],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    });
+    let circuit: SerialCircuit = serde_json::from_value(absent.clone()).unwrap();
+    assert!(circuit.implicit_permutation.is_empty());
+    let mut expected = absent;
+    expected["implicit_permutation"] = json!([]);
+    assert_json_eq!(serde_json::to_value(&circuit).unwrap(), expected);
+}
+
+#[test]
+fn supported_schema_versions() {
+    assert_eq!(tket_json_rs::SUPPORTED_CIRCUIT_SCHEMA, "circuit_v1");
+    assert_eq!(tket_json_rs::SUPPORTED_PASS_SCHEMA, "compiler_pass_v1");
+    assert!(tket_json_rs::supports_version("circuit_v1"));
+    assert!(tket_json_rs::supports_version("compiler_pass_v1"));
+    assert!(!tket_json_rs::supports_version("circuit_v2"));
+    assert!(!tket_json_rs::supports_version(""));
+
+    // Pass schemas are not accepted as circuit schemas.
+    let mut circuit = SerialCircuit::<String>::new(None, "0".to_string());
+    circuit.schema_version = Some(tket_json_rs::SUPPORTED_PASS_SCHEMA.to_string());
+    assert_eq!(
+        circuit.check_schema_version(),
+        SchemaVersionCheck::Unsupported("compiler_pass_v1".to_string())
+    );
+}
+
+#[test]
+fn readout_order() {
+    let circuit_json = json!({
+        "bits": [["c", [0]], ["c", [1]], ["c", [2]]],
+        "commands": [
+            {"args": [["q", [0]], ["c", [2]]], "op": {"type": "Measure"}},
+            {"args": [["q", [1]], ["c", [1]]], "op": {"type": "Measure"}},
+            {"args": [["q", [1]], ["c", [0]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    });
+    let circuit: SerialCircuit = serde_json::from_value(circuit_json.clone()).unwrap();
+    let c = |i| ElementId("c".to_string(), vec![i]);
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    assert_eq!(circuit.readout_order(), [&c(0), &c(1), &c(2)]);
+    assert_eq!(
+        circuit.qubit_readout(),
+        HashMap::from([(&q(0), 2), (&q(1), 0)])
+    );
+
+    // The readout is computed, so it is not written.
+    let reencoded_json = serde_json::to_value(&circuit).unwrap();
+    assert_json_eq!(reencoded_json, circuit_json);
+
+    // Readout maps written by other producers are ignored.
+    let mut recorded_json = circuit_json.clone();
+    recorded_json["bit_readout"] = json!([[["c", [0]], 2], [["c", [1]], 0], [["c", [2]], 1]]);
+    let recorded: SerialCircuit = serde_json::from_value(recorded_json).unwrap();
+    assert_eq!(recorded, circuit);
+}
+
+#[rstest]
+#[case::compact(false)]
+#[case::pretty(true)]
+fn write_json(#[case] pretty: bool) {
+    let circuit: SerialCircuit = serde_json::from_str(SIMPLE).unwrap();
+
+    let mut buffer = Vec::new();
+    if pretty {
+        circuit.write_json_pretty(&mut buffer).unwrap();
+    } else {
+        circuit.write_json(&mut buffer).unwrap();
+    }
+    assert_eq!(buffer.contains(&b'\n'), pretty);
+
+    let reparsed: SerialCircuit = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(reparsed, circuit);
+}
+
+#[test]
+fn dag_wires_are_rejected() {
+    let circuit_json = json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}, "wires": [[0, 2], [1, 3]]}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    });
+    let err = serde_json::from_value::<SerialCircuit>(circuit_json)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("explicit wires are not supported"), "{err}");
+}
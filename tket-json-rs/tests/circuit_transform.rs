@@ -0,0 +1,1009 @@
+//! Tests for the in-place circuit rewrites.
+use std::collections::{HashMap, HashSet};
+
+use serde_json::json;
+use tket_json_rs::circuit_json::transform::{
+    ConjugateError, DaggerError, PadError, PermutationError, RebaseError, RegisterError,
+    ReplaceError, SubcircuitError,
+};
+use tket_json_rs::opbox::OpBox;
+use tket_json_rs::register::{ElementId, Qubit};
+use tket_json_rs::{OpType, SerialCircuit};
+
+#[test]
+fn canonicalize_sorts_declarations() {
+    let shuffled: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [1]], ["b", [0]], ["c", [0]]],
+        "commands": [{"args": [["q", [1]], ["a", [0]]], "op": {"type": "CX"}}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [1]], ["q", [0]], ["a", [0]]]
+    }))
+    .unwrap();
+    let mut reversed = shuffled.clone();
+    reversed.qubits.reverse();
+    reversed.bits.reverse();
+
+    let mut canonical = shuffled.clone();
+    canonical.canonicalize();
+    reversed.canonicalize();
+
+    assert_eq!(
+        serde_json::to_value(&canonical).unwrap(),
+        json!({
+            "bits": [["b", [0]], ["c", [0]], ["c", [1]]],
+            "commands": [{"args": [["q", [1]], ["a", [0]]], "op": {"type": "CX"}}],
+            "implicit_permutation": [],
+            "phase": "0",
+            "qubits": [["a", [0]], ["q", [0]], ["q", [1]]]
+        })
+    );
+    assert_eq!(canonical, reversed);
+    assert_eq!(canonical.commands, shuffled.commands);
+}
+
+fn bell_with_spectator() -> SerialCircuit {
+    serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]]], "op": {"type": "X"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [
+            [["q", [0]], ["q", [0]]],
+            [["q", [1]], ["q", [1]]]
+        ],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn subcircuit_single_qubit_slice() {
+    let circuit = bell_with_spectator();
+    let q0 = ElementId("q".to_string(), vec![0]);
+
+    let slice = circuit.subcircuit(std::slice::from_ref(&q0));
+    let op_types: Vec<OpType> = slice.commands.iter().map(|c| c.op.op_type).collect();
+    assert_eq!(op_types, vec![OpType::H, OpType::Measure]);
+    assert_eq!(slice.qubits, vec![Qubit::from(q0.clone())]);
+    assert_eq!(slice.bits, circuit.bits);
+    assert_eq!(slice.implicit_permutation.len(), 1);
+
+    assert_eq!(
+        circuit.strict_subcircuit(&[q0]),
+        Err(SubcircuitError::CommandCrossesBoundary {
+            index: 1,
+            qubit: ElementId("q".to_string(), vec![1]),
+        })
+    );
+}
+
+#[test]
+fn replace_gate_t_with_rz() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "T"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]]], "op": {"type": "T"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    let rz: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{"args": [["r", [0]]], "op": {"type": "Rz", "params": ["0.25"]}}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["r", [0]]]
+    }))
+    .unwrap();
+    let before_replacement = circuit.clone();
+
+    circuit.replace_gate(OpType::T, &rz).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&circuit).unwrap(),
+        json!({
+            "bits": [],
+            "commands": [
+                {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.25"]}},
+                {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+                {"args": [["q", [1]]], "op": {"type": "Rz", "params": ["0.25"]}}
+            ],
+            "implicit_permutation": [],
+            "phase": "0",
+            "qubits": [["q", [0]], ["q", [1]]]
+        })
+    );
+
+    // The phase of each substitution is folded into the global phase.
+    let mut phased = before_replacement.clone();
+    let mut rz_phased = rz.clone();
+    rz_phased.phase = "0.125".to_string();
+    phased.replace_gate(OpType::T, &rz_phased).unwrap();
+    assert_eq!(phased.phase, "0.25");
+
+    // The replacement acts on a single qubit, so it cannot replace a CX.
+    let before = circuit.clone();
+    assert_eq!(
+        circuit.replace_gate(OpType::CX, &rz),
+        Err(ReplaceError::ArityMismatch {
+            index: 1,
+            expected: 1,
+            found: 2
+        })
+    );
+    assert_eq!(circuit, before);
+}
+
+#[test]
+fn replace_gate_rejects_unmapped_units_and_params() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.5"]}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+    let before = circuit.clone();
+
+    // The replacement uses an ancilla it does not declare.
+    let with_ancilla: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["r", [0]], ["a", [0]]], "op": {"type": "CX"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["r", [0]]]
+    }))
+    .unwrap();
+    assert_eq!(
+        circuit.replace_gate(OpType::H, &with_ancilla),
+        Err(ReplaceError::UnmappedUnit {
+            unit: ElementId("a".to_string(), vec![0]),
+        })
+    );
+
+    // Declaring the ancilla makes the replacement wider than the gate.
+    let mut declared = with_ancilla.clone();
+    declared
+        .qubits
+        .push(Qubit::from(ElementId("a".to_string(), vec![0])));
+    assert_eq!(
+        circuit.replace_gate(OpType::H, &declared),
+        Err(ReplaceError::ArityMismatch {
+            index: 0,
+            expected: 2,
+            found: 1
+        })
+    );
+
+    // The angle of the replaced rotation would be lost.
+    let sx: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{"args": [["r", [0]]], "op": {"type": "SX"}}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["r", [0]]]
+    }))
+    .unwrap();
+    assert_eq!(
+        circuit.replace_gate(OpType::Rz, &sx),
+        Err(ReplaceError::ParameterisedCommand { index: 1 })
+    );
+    assert_eq!(circuit, before);
+}
+
+#[test]
+fn remove_adjacent_inverses() {
+    let circuit = |commands: serde_json::Value| -> SerialCircuit {
+        serde_json::from_value(json!({
+            "bits": [],
+            "commands": commands,
+            "implicit_permutation": [],
+            "phase": "0",
+            "qubits": [["q", [0]], ["q", [1]]]
+        }))
+        .unwrap()
+    };
+
+    // Adjacent H gates cancel, leaving the S gate on the other qubit.
+    let mut adjacent = circuit(json!([
+        {"args": [["q", [0]]], "op": {"type": "H"}},
+        {"args": [["q", [1]]], "op": {"type": "S"}},
+        {"args": [["q", [0]]], "op": {"type": "H"}}
+    ]));
+    assert_eq!(adjacent.remove_adjacent_inverses(), 1);
+    assert_eq!(adjacent.commands.len(), 1);
+    assert_eq!(adjacent.commands[0].op.op_type, OpType::S);
+
+    // Cancellations cascade through T, Tdg.
+    let mut nested = circuit(json!([
+        {"args": [["q", [0]]], "op": {"type": "H"}},
+        {"args": [["q", [0]]], "op": {"type": "T"}},
+        {"args": [["q", [0]]], "op": {"type": "Tdg"}},
+        {"args": [["q", [0]]], "op": {"type": "H"}}
+    ]));
+    assert_eq!(nested.remove_adjacent_inverses(), 2);
+    assert!(nested.commands.is_empty());
+
+    // An intervening CX blocks the cancellation.
+    let mut blocked = circuit(json!([
+        {"args": [["q", [0]]], "op": {"type": "H"}},
+        {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+        {"args": [["q", [0]]], "op": {"type": "H"}}
+    ]));
+    let before = blocked.clone();
+    assert_eq!(blocked.remove_adjacent_inverses(), 0);
+    assert_eq!(blocked, before);
+}
+
+#[test]
+fn reverse_qubit_order() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]], ["c", [0]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [[["q", [0]], ["q", [1]]], [["q", [1]], ["q", [0]]]],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+
+    circuit.reverse_qubit_order();
+
+    assert_eq!(
+        serde_json::to_value(&circuit).unwrap(),
+        json!({
+            "bits": [["c", [0]]],
+            "commands": [
+                {"args": [["q", [1]]], "op": {"type": "H"}},
+                {"args": [["q", [1]], ["q", [0]]], "op": {"type": "CX"}},
+                {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}
+            ],
+            "implicit_permutation": [[["q", [1]], ["q", [0]]], [["q", [0]], ["q", [1]]]],
+            "phase": "0",
+            "qubits": [["q", [1]], ["q", [0]]]
+        })
+    );
+
+    // Reversing twice is the identity.
+    let reversed = circuit.clone();
+    circuit.reverse_qubit_order();
+    circuit.reverse_qubit_order();
+    assert_eq!(circuit, reversed);
+}
+
+#[test]
+fn barrier_layers() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [1]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [2]]], "op": {"type": "X"}},
+            {"args": [["q", [1]], ["q", [2]]], "op": {"type": "CX"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    }))
+    .unwrap();
+    assert_eq!(circuit.layers(), vec![vec![0, 1, 3], vec![2], vec![4]]);
+
+    circuit.barrier_layers();
+
+    let barrier = json!({
+        "args": [["q", [0]], ["q", [1]], ["q", [2]]],
+        "op": {"signature": ["Q", "Q", "Q"], "type": "Barrier"}
+    });
+    let reencoded_json = serde_json::to_value(&circuit).unwrap();
+    assert_eq!(
+        reencoded_json["commands"],
+        json!([
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [1]]], "op": {"type": "H"}},
+            {"args": [["q", [2]]], "op": {"type": "X"}},
+            barrier,
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            barrier,
+            {"args": [["q", [1]], ["q", [2]]], "op": {"type": "CX"}}
+        ])
+    );
+    let roundtrip: SerialCircuit = serde_json::from_value(reencoded_json).unwrap();
+    assert_eq!(roundtrip, circuit);
+}
+
+#[test]
+fn split_at_measurements() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {"args": [["q", [1]]], "op": {"type": "X"}},
+            {"args": [["q", [1]], ["c", [1]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0.5",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+
+    let segments = circuit.split_at_measurements();
+    assert_eq!(segments.len(), 2);
+
+    let op_types = |c: &SerialCircuit| c.commands.iter().map(|c| c.op.op_type).collect::<Vec<_>>();
+    assert_eq!(
+        op_types(&segments[0]),
+        vec![OpType::H, OpType::CX, OpType::Measure]
+    );
+    assert_eq!(op_types(&segments[1]), vec![OpType::X, OpType::Measure]);
+    for segment in &segments {
+        assert_eq!(segment.qubits, circuit.qubits);
+        assert_eq!(segment.bits, circuit.bits);
+    }
+    assert_eq!(segments[0].phase, "0");
+    assert_eq!(segments[1].phase, "0.5");
+}
+
+#[test]
+fn materialize_registers() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]], ["q", [2]]], "op": {"type": "CX"}},
+            {"args": [["q", [2]], ["c", [0]]], "op": {"type": "Measure"}},
+            {
+                "args": [["c", [0]], ["q", [1]]],
+                "op": {
+                    "type": "Conditional",
+                    "conditional": {"op": {"type": "X"}, "width": 1, "value": 1}
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+
+    circuit.materialize_registers();
+    let qubits: Vec<_> = circuit.qubits.iter().map(|q| q.id.clone()).collect();
+    let bits: Vec<_> = circuit.bits.iter().map(|b| b.id.clone()).collect();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    assert_eq!(qubits, vec![q(0), q(2), q(1)]);
+    assert_eq!(bits, vec![ElementId("c".to_string(), vec![0])]);
+
+    // Materializing an explicit circuit is a no-op.
+    let before = circuit.clone();
+    circuit.materialize_registers();
+    assert_eq!(circuit, before);
+}
+
+#[test]
+fn permute_qubits() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [2]]], "op": {"type": "H"}}
+        ],
+        "implicit_permutation": [[["q", [0]], ["q", [1]]], [["q", [1]], ["q", [0]]]],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    }))
+    .unwrap();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+
+    // A 3-cycle on all the qubits.
+    let perm = HashMap::from([(q(0), q(1)), (q(1), q(2)), (q(2), q(0))]);
+    circuit.permute_qubits(&perm).unwrap();
+    assert_eq!(circuit.commands[0].args, vec![q(1), q(2)]);
+    assert_eq!(circuit.commands[1].args, vec![q(0)]);
+    assert_eq!(circuit.implicit_permutation[0].0.id, q(1));
+    assert_eq!(circuit.implicit_permutation[0].1.id, q(2));
+
+    // Two qubits mapped onto the same one.
+    let before = circuit.clone();
+    let collapsing = HashMap::from([(q(0), q(2)), (q(1), q(2))]);
+    assert_eq!(
+        circuit.permute_qubits(&collapsing),
+        Err(PermutationError::NotBijective { qubit: q(2) })
+    );
+    // Moving a qubit onto one that stays in place.
+    let partial = HashMap::from([(q(0), q(1))]);
+    assert_eq!(
+        circuit.permute_qubits(&partial),
+        Err(PermutationError::NotBijective { qubit: q(1) })
+    );
+    let unknown = HashMap::from([(q(0), q(5)), (q(5), q(0))]);
+    assert_eq!(
+        circuit.permute_qubits(&unknown),
+        Err(PermutationError::UnknownQubit { qubit: q(5) })
+    );
+    assert_eq!(circuit, before);
+}
+
+#[test]
+fn inline_circboxes() {
+    let circbox = |commands: serde_json::Value, phase: &str| {
+        json!({
+            "type": "CircBox",
+            "box": {
+                "type": "CircBox",
+                "id": "4e3d2c1b-0a9f-4e8d-b7c6-a5b4c3d2e1f0",
+                "circuit": {
+                    "bits": [],
+                    "commands": commands,
+                    "implicit_permutation": [],
+                    "phase": phase,
+                    "qubits": [["q", [0]], ["q", [1]]]
+                }
+            }
+        })
+    };
+    let inner = circbox(json!([{"args": [["q", [1]]], "op": {"type": "T"}}]), "0");
+    let outer = circbox(
+        json!([
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]], ["q", [0]]], "op": inner}
+        ]),
+        "0.25",
+    );
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["a", [0]]], "op": {"type": "H"}},
+            {"args": [["a", [1]], ["a", [0]]], "op": outer},
+            {"args": [["a", [1]]], "op": {"type": "X"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["a", [0]], ["a", [1]]]
+    }))
+    .unwrap();
+
+    let mut kept = circuit.clone();
+    kept.inline_circboxes(&HashSet::from([OpType::CircBox]));
+    assert_eq!(kept, circuit);
+
+    circuit.inline_circboxes(&HashSet::new());
+    let a = |i| ElementId("a".to_string(), vec![i]);
+    let commands: Vec<(OpType, Vec<ElementId>)> = circuit
+        .commands
+        .iter()
+        .map(|c| (c.op.op_type, c.args.clone()))
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            (OpType::H, vec![a(0)]),
+            (OpType::CX, vec![a(1), a(0)]),
+            (OpType::T, vec![a(1)]),
+            (OpType::X, vec![a(1)]),
+        ]
+    );
+    assert_eq!(circuit.phase, "0.25");
+
+    // A box whose circuit acts on an undeclared unit is kept.
+    let leaky = circbox(json!([{"args": [["anc", [0]]], "op": {"type": "H"}}]), "0");
+    let mut kept: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{"args": [["a", [0]], ["a", [1]]], "op": leaky}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["a", [0]], ["a", [1]]]
+    }))
+    .unwrap();
+    let before = kept.clone();
+    kept.inline_circboxes(&HashSet::new());
+    assert_eq!(kept, before);
+}
+
+#[test]
+fn inline_circboxes_in_custom_gates() {
+    let circbox = json!({
+        "type": "CircBox",
+        "box": {
+            "type": "CircBox",
+            "id": "4e3d2c1b-0a9f-4e8d-b7c6-a5b4c3d2e1f0",
+            "circuit": {
+                "bits": [],
+                "commands": [{"args": [["q", [0]]], "op": {"type": "T"}}],
+                "implicit_permutation": [],
+                "phase": "0",
+                "qubits": [["q", [0]]]
+            }
+        }
+    });
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{
+            "args": [["a", [0]]],
+            "op": {
+                "type": "CustomGate",
+                "box": {
+                    "type": "CustomGate",
+                    "id": "7b6a5c4d-3e2f-4a1b-9c8d-7e6f5a4b3c2d",
+                    "gate": {
+                        "name": "wrapped_t",
+                        "args": [],
+                        "definition": {
+                            "bits": [],
+                            "commands": [{"args": [["q", [0]]], "op": circbox}],
+                            "implicit_permutation": [],
+                            "phase": "0",
+                            "qubits": [["q", [0]]]
+                        }
+                    },
+                    "params": []
+                }
+            }
+        }],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["a", [0]]]
+    }))
+    .unwrap();
+
+    let mut kept = circuit.clone();
+    kept.inline_circboxes(&HashSet::from([OpType::CustomGate]));
+    assert_eq!(kept, circuit);
+
+    // The gate is kept, with the box in its definition inlined.
+    circuit.inline_circboxes(&HashSet::new());
+    assert_eq!(circuit.commands.len(), 1);
+    let Some(OpBox::CustomGate { gate, .. }) = &circuit.commands[0].op.op_box else {
+        panic!("Expected a CustomGate, got {:?}", circuit.commands[0].op);
+    };
+    let definition: Vec<OpType> = gate
+        .definition
+        .commands
+        .iter()
+        .map(|c| c.op.op_type)
+        .collect();
+    assert_eq!(definition, vec![OpType::T]);
+}
+
+#[test]
+fn merge_single_qubit_rotations() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.25"]}},
+            {"args": [["q", [1]]], "op": {"type": "Rz", "params": ["a"]}},
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0.5"]}},
+            {"args": [["q", [1]]], "op": {"type": "Rz", "params": ["2*b"]}},
+            {"args": [["q", [0]]], "op": {"type": "Rx", "params": ["0.5"]}},
+            {"args": [["q", [0]]], "op": {"type": "Rx", "params": ["1"]}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [1]]], "op": {"type": "Rz", "params": ["c"]}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+
+    assert_eq!(circuit.merge_single_qubit_rotations(), 3);
+    let commands: Vec<(OpType, &[String])> = circuit
+        .commands
+        .iter()
+        .map(|c| (c.op.op_type, c.op.params()))
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            (OpType::Rz, &["0.75".to_string()][..]),
+            (OpType::Rz, &["a + 2*b".to_string()][..]),
+            (OpType::Rx, &["1.5".to_string()][..]),
+            (OpType::CX, &[][..]),
+            (OpType::Rz, &["c".to_string()][..]),
+        ]
+    );
+    assert_eq!(circuit.merge_single_qubit_rotations(), 0);
+}
+
+#[test]
+fn dagger() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "S"}},
+            {"args": [["q", [0]]], "op": {"type": "TK1", "params": ["0.1", "0.2", "0.3"]}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "Barrier", "signature": ["Q", "Q"]}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0.25",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+
+    let dagger = circuit.dagger().unwrap();
+    assert_eq!(
+        serde_json::to_value(&dagger.commands).unwrap(),
+        json!([
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "Barrier", "signature": ["Q", "Q"]}},
+            {"args": [["q", [0]]], "op": {"type": "TK1", "params": ["-0.3", "-0.2", "-0.1"]}},
+            {"args": [["q", [0]]], "op": {"type": "Sdg"}}
+        ])
+    );
+    assert_eq!(dagger.phase, "-0.25");
+    assert_eq!(dagger.dagger().unwrap().commands, circuit.commands);
+
+    let mut measured = circuit.clone();
+    measured.commands.push(
+        serde_json::from_value(
+            json!({"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}),
+        )
+        .unwrap(),
+    );
+    assert_eq!(
+        measured.dagger(),
+        Err(DaggerError::NotInvertible {
+            index: 4,
+            op_type: OpType::Measure
+        })
+    );
+}
+
+#[test]
+fn pad_with_identity() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [2]]], "op": {"type": "X"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    }))
+    .unwrap();
+
+    assert_eq!(
+        circuit.pad_with_identity(OpType::CX),
+        Err(PadError::NotIdentityGate {
+            op_type: OpType::CX
+        })
+    );
+    // Padding with X would change the circuit.
+    assert_eq!(
+        circuit.pad_with_identity(OpType::X),
+        Err(PadError::NotIdentityGate { op_type: OpType::X })
+    );
+    assert_eq!(circuit.commands.len(), 3);
+
+    circuit.pad_with_identity(OpType::noop).unwrap();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    let commands: Vec<(OpType, Vec<ElementId>)> = circuit
+        .commands
+        .iter()
+        .map(|c| (c.op.op_type, c.args.clone()))
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            // Layer 0: q[1] is idle.
+            (OpType::H, vec![q(0)]),
+            (OpType::X, vec![q(2)]),
+            (OpType::noop, vec![q(1)]),
+            // Layer 1: q[2] is idle.
+            (OpType::CX, vec![q(0), q(1)]),
+            (OpType::noop, vec![q(2)]),
+        ]
+    );
+}
+
+#[test]
+fn prepend_resets() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [1]]], "op": {"type": "Reset"}},
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    }))
+    .unwrap();
+
+    circuit.prepend_resets();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+    let expected = vec![
+        (OpType::Reset, vec![q(0)]),
+        (OpType::Reset, vec![q(2)]),
+        (OpType::Reset, vec![q(1)]),
+        (OpType::H, vec![q(0)]),
+        (OpType::CX, vec![q(0), q(1)]),
+    ];
+    let commands = |circuit: &SerialCircuit| -> Vec<(OpType, Vec<ElementId>)> {
+        circuit
+            .commands
+            .iter()
+            .map(|c| (c.op.op_type, c.args.clone()))
+            .collect()
+    };
+    assert_eq!(commands(&circuit), expected);
+
+    // Resets are only added once.
+    circuit.prepend_resets();
+    assert_eq!(commands(&circuit), expected);
+}
+
+#[test]
+fn dagger_unitary_box() {
+    let circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {
+                "args": [["q", [0]]],
+                "op": {
+                    "type": "Unitary1qBox",
+                    "box": {
+                        "type": "Unitary1qBox",
+                        "id": "2b3c4d5e-6f70-4182-93a4-b5c6d7e8f901",
+                        "matrix": [[[1.0, 0.0], [0.0, 0.0]], [[0.0, 0.0], [0.0, 1.0]]]
+                    }
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+
+    let dagger = circuit.dagger().unwrap();
+    assert_eq!(dagger.commands[0].op.op_type, OpType::Unitary1qBox);
+    let Some(OpBox::Unitary1qBox { matrix, .. }) = &dagger.commands[0].op.op_box else {
+        panic!("Expected a Unitary1qBox");
+    };
+    assert_eq!(
+        matrix,
+        &[[(1.0, 0.0), (0.0, 0.0)], [(0.0, 0.0), (0.0, -1.0)]]
+    );
+    assert_eq!(dagger.commands[1].op.op_type, OpType::H);
+}
+
+#[test]
+fn prune_unused_units() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}
+        ],
+        "created_qubits": [["q", [2]]],
+        "implicit_permutation": [
+            [["q", [0]], ["q", [0]]],
+            [["q", [1]], ["q", [1]]],
+            [["q", [2]], ["q", [2]]]
+        ],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]], ["q", [2]]]
+    }))
+    .unwrap();
+
+    assert_eq!(circuit.prune_unused_units(), (1, 1));
+    assert_eq!(
+        serde_json::to_value(&circuit).unwrap(),
+        json!({
+            "bits": [["c", [0]]],
+            "commands": [
+                {"args": [["q", [0]]], "op": {"type": "H"}},
+                {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}
+            ],
+            "created_qubits": [["q", [2]]],
+            "implicit_permutation": [
+                [["q", [0]], ["q", [0]]],
+                [["q", [2]], ["q", [2]]]
+            ],
+            "phase": "0",
+            "qubits": [["q", [0]], ["q", [2]]]
+        })
+    );
+    assert_eq!(circuit.prune_unused_units(), (0, 0));
+}
+
+#[test]
+fn remove_phase_ops() {
+    let initial_json = json!({
+        "bits": [],
+        "commands": [
+            {"args": [], "op": {"type": "Phase", "params": ["0.25"]}},
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [], "op": {"type": "Phase", "params": ["0.5"]}},
+            {"args": [], "op": {"type": "Phase", "params": ["a"]}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0.125",
+        "qubits": [["q", [0]]]
+    });
+    let mut circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&circuit).unwrap(), initial_json);
+    assert_eq!(circuit.commands[0].op.op_type, OpType::Phase);
+
+    circuit.remove_phase_ops();
+    assert_eq!(circuit.phase, "0.875 + a");
+    assert_eq!(circuit.commands.len(), 1);
+    assert_eq!(circuit.commands[0].op.op_type, OpType::H);
+}
+
+#[test]
+fn rebase_basic() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CZ"}},
+            {"args": [["q", [1]]], "op": {"type": "S"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    circuit.rebase_basic().unwrap();
+
+    let gates: Vec<(OpType, Vec<String>, &[String])> = circuit
+        .commands
+        .iter()
+        .map(|c| {
+            let args = c.args.iter().map(ToString::to_string).collect();
+            (c.op.op_type, args, c.op.params())
+        })
+        .collect();
+    let q = |i: usize| format!("q[{i}]");
+    assert_eq!(
+        gates,
+        [
+            (OpType::H, vec![q(1)], [].as_slice()),
+            (OpType::CX, vec![q(0), q(1)], &[]),
+            (OpType::H, vec![q(1)], &[]),
+            (OpType::Rz, vec![q(1)], &["0.5".to_string()]),
+        ]
+    );
+    assert_eq!(circuit.phase, "0.25");
+
+    let mut boxed = circuit.clone();
+    boxed.commands.push(
+        serde_json::from_value(json!({
+            "args": [["q", [0]]],
+            "op": {
+                "type": "Unitary1qBox",
+                "box": {
+                    "type": "Unitary1qBox",
+                    "id": "6a3a3ba7-1a5c-4a0a-8a5c-0f2fbd8c0e10",
+                    "matrix": [[[1, 0], [0, 0]], [[0, 0], [1, 0]]]
+                }
+            }
+        }))
+        .unwrap(),
+    );
+    assert_eq!(
+        boxed.rebase_basic(),
+        Err(RebaseError::UnsupportedOperation {
+            index: 4,
+            op_type: OpType::Unitary1qBox
+        })
+    );
+    assert_eq!(boxed.commands.len(), 5);
+}
+
+#[test]
+fn import_registers() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": [{"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+    let other: SerialCircuit = serde_json::from_value(json!({
+        "bits": [["d", [0]], ["d", [1]]],
+        "commands": [{"args": [["q", [0]], ["a", [0]]], "op": {"type": "CX"}}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["a", [0]]]
+    }))
+    .unwrap();
+
+    circuit.import_registers(&other).unwrap();
+    let qubits: Vec<String> = circuit.qubits.iter().map(ToString::to_string).collect();
+    let bits: Vec<String> = circuit.bits.iter().map(ToString::to_string).collect();
+    assert_eq!(qubits, ["q[0]", "a[0]"]);
+    assert_eq!(bits, ["c[0]", "d[0]", "d[1]"]);
+    assert_eq!(circuit.commands.len(), 1);
+
+    let mut conflicting = other.clone();
+    conflicting
+        .bits
+        .push(ElementId("q".to_string(), vec![1]).into());
+    assert_eq!(
+        circuit.clone().import_registers(&conflicting),
+        Err(RegisterError::KindMismatch {
+            register: "q".to_string()
+        })
+    );
+}
+
+#[test]
+fn conjugate_by() {
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [{"args": [["q", [1]]], "op": {"type": "Rz", "params": ["a"]}}],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    let compute: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "S"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0.25",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap();
+    circuit.conjugate_by(&compute).unwrap();
+
+    let op_types: Vec<OpType> = circuit.commands.iter().map(|c| c.op.op_type).collect();
+    assert_eq!(
+        op_types,
+        [OpType::S, OpType::CX, OpType::Rz, OpType::CX, OpType::Sdg]
+    );
+    assert_eq!(circuit.commands[..2], compute.commands[..]);
+    assert_eq!(
+        circuit.commands[3..],
+        compute.dagger().unwrap().commands[..]
+    );
+    assert_eq!(circuit.phase, "0");
+
+    let mut measured = compute.clone();
+    measured
+        .bits
+        .push(ElementId("c".to_string(), vec![0]).into());
+    measured.commands.push(
+        serde_json::from_value(json!({
+            "args": [["q", [0]], ["c", [0]]],
+            "op": {"type": "Measure"}
+        }))
+        .unwrap(),
+    );
+    let before = circuit.clone();
+    assert_eq!(
+        circuit.conjugate_by(&measured),
+        Err(ConjugateError::UndeclaredUnit {
+            unit: ElementId("c".to_string(), vec![0])
+        })
+    );
+    assert_eq!(circuit, before);
+}
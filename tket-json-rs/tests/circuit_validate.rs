@@ -0,0 +1,55 @@
+//! Tests for the structural validation of circuits.
+use rstest::rstest;
+use serde_json::json;
+use tket_json_rs::circuit_json::validate::CircuitError;
+use tket_json_rs::SerialCircuit;
+
+/// A circuit applying an `X` conditioned on the given bits and value.
+fn conditional_x(control_bits: &[i64], width: u32, value: u32) -> SerialCircuit {
+    let mut args: Vec<_> = control_bits.iter().map(|&i| json!(["c", [i]])).collect();
+    args.push(json!(["q", [0]]));
+    serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {
+                "args": args,
+                "op": {
+                    "type": "Conditional",
+                    "conditional": {"op": {"type": "X"}, "width": width, "value": value}
+                }
+            }
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap()
+}
+
+#[rstest]
+#[case::single_bit(&[0], 1, 1)]
+#[case::two_bits(&[0, 1], 2, 3)]
+fn valid_conditional(#[case] control_bits: &[i64], #[case] width: u32, #[case] value: u32) {
+    assert_eq!(conditional_x(control_bits, width, value).validate(), Ok(()));
+}
+
+#[test]
+fn conditional_width_mismatch() {
+    assert_eq!(
+        conditional_x(&[0], 2, 1).validate(),
+        Err(CircuitError::ConditionalWidthMismatch {
+            index: 1,
+            width: 2,
+            n_control_bits: 1
+        })
+    );
+    assert_eq!(
+        conditional_x(&[0, 1], 2, 4).validate(),
+        Err(CircuitError::ConditionalValueOutOfRange {
+            index: 1,
+            value: 4,
+            width: 2
+        })
+    );
+}
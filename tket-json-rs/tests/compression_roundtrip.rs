@@ -0,0 +1,30 @@
+//! Roundtrip tests for gzip-compressed circuit JSON.
+#![cfg(feature = "compression")]
+
+use rstest::rstest;
+use tket_json_rs::SerialCircuit;
+
+const SIMPLE: &str = include_str!("data/circuit/simple.json");
+const CLASSICAL: &str = include_str!("data/circuit/classical.json");
+const WASM: &str = include_str!("data/circuit/wasm.json");
+
+#[rstest]
+#[case::simple(SIMPLE)]
+#[case::classical(CLASSICAL)]
+#[case::wasm(WASM)]
+fn gzip_roundtrip(#[case] json: &str) {
+    let circuit: SerialCircuit = serde_json::from_str(json).unwrap();
+
+    let mut buffer = Vec::new();
+    circuit.to_gzip_json(&mut buffer).unwrap();
+    // Gzip magic bytes.
+    assert_eq!(buffer[..2], [0x1f, 0x8b]);
+
+    let decoded = SerialCircuit::from_gzip_json(buffer.as_slice()).unwrap();
+    assert_eq!(decoded, circuit);
+}
+
+#[test]
+fn invalid_gzip() {
+    assert!(SerialCircuit::from_gzip_json(&b"{}"[..]).is_err());
+}
@@ -0,0 +1,452 @@
+//! Roundtrip tests for the typed box operations.
+use assert_json_diff::assert_json_eq;
+use serde_json::{json, Value};
+use tket_json_rs::circuit_json::{Matrix, Permutation};
+use tket_json_rs::opbox::{OpBox, ToffoliBoxSynthStrat};
+use tket_json_rs::register::Bitstring;
+use tket_json_rs::{OpType, SerialCircuit};
+
+/// Wraps a box into a single-command circuit acting on `n_qubits` qubits,
+/// checks that it roundtrips, and returns the parsed box.
+fn roundtrip_box(op_box: Value, n_qubits: i64) -> OpBox {
+    let qubits: Vec<Value> = (0..n_qubits).map(|i| json!(["q", [i]])).collect();
+    let initial_json = json!({
+        "bits": [],
+        "commands": [{
+            "args": qubits,
+            "op": {"type": op_box["type"], "box": op_box}
+        }],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": qubits
+    });
+
+    let circuit: SerialCircuit = serde_json::from_value(initial_json.clone()).unwrap();
+    let reencoded_json = serde_json::to_value(&circuit).unwrap();
+    assert_json_eq!(reencoded_json, initial_json);
+
+    circuit.commands[0].op.op_box.clone().unwrap()
+}
+
+#[test]
+fn toffoli_box() {
+    let op_box = roundtrip_box(
+        json!({
+            "type": "ToffoliBox",
+            "id": "3b0c7e85-5b5e-4b0e-9a59-8a0b0f7f2c01",
+            "permutation": [
+                [[false, true], [true, false]],
+                [[true, false], [false, true]]
+            ],
+            "strat": "Matching",
+            "rotation_axis": "Ry"
+        }),
+        2,
+    );
+
+    let OpBox::ToffoliBox {
+        permutation,
+        strat,
+        rotation_axis,
+        ..
+    } = op_box
+    else {
+        panic!("Expected a ToffoliBox");
+    };
+    assert_eq!(
+        permutation,
+        Permutation(vec![
+            (vec![false, true], vec![true, false]),
+            (vec![true, false], vec![false, true]),
+        ])
+    );
+    assert_eq!(strat, ToffoliBoxSynthStrat::Matching);
+    assert_eq!(rotation_axis, Some(OpType::Ry));
+}
+
+#[test]
+fn multiplexor_boxes() {
+    for box_type in ["MultiplexorBox", "MultiplexedRotationBox"] {
+        let op_box = roundtrip_box(
+            json!({
+                "type": box_type,
+                "id": "6f1d2c3b-4a5e-4f60-8b7c-9d0e1f2a3b4c",
+                "op_map": [
+                    [[false], {"type": "Rz", "params": ["0.5"]}],
+                    [[true], {"type": "Rz", "params": ["1.5"]}]
+                ]
+            }),
+            2,
+        );
+        let ops = op_box.operations();
+        assert_eq!(ops.len(), 2);
+        assert!(ops.iter().all(|op| op.op_type == OpType::Rz));
+    }
+}
+
+#[test]
+fn conjugation_box() {
+    let op_box = roundtrip_box(
+        json!({
+            "type": "ConjugationBox",
+            "id": "a1b2c3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d",
+            "compute": {"type": "H"},
+            "action": {"type": "Rz", "params": ["0.5"]},
+            "uncompute": {"type": "H"}
+        }),
+        1,
+    );
+    let op_types: Vec<OpType> = op_box.operations().iter().map(|op| op.op_type).collect();
+    assert_eq!(op_types, vec![OpType::H, OpType::Rz, OpType::H]);
+
+    // The uncompute operation is optional.
+    let op_box = roundtrip_box(
+        json!({
+            "type": "ConjugationBox",
+            "id": "a1b2c3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d",
+            "compute": {"type": "CX"},
+            "action": {"type": "CZ"},
+            "uncompute": null
+        }),
+        2,
+    );
+    assert!(matches!(
+        op_box,
+        OpBox::ConjugationBox {
+            uncompute: None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn state_preparation_box() {
+    let op_box = roundtrip_box(
+        json!({
+            "type": "StatePreparationBox",
+            "id": "0f9e8d7c-6b5a-4f3e-9d2c-1b0a9f8e7d6c",
+            "statevector": [
+                [[0.6, 0.0]],
+                [[0.0, 0.0]],
+                [[0.0, 0.0]],
+                [[0.0, 0.8]]
+            ],
+            "is_inverse": false,
+            "with_initial_reset": true
+        }),
+        2,
+    );
+
+    let OpBox::StatePreparationBox {
+        statevector,
+        is_inverse,
+        with_initial_reset,
+        ..
+    } = op_box
+    else {
+        panic!("Expected a StatePreparationBox");
+    };
+    assert_eq!(
+        statevector,
+        Matrix {
+            data: vec![
+                vec![(0.6, 0.0)],
+                vec![(0.0, 0.0)],
+                vec![(0.0, 0.0)],
+                vec![(0.0, 0.8)],
+            ]
+        }
+    );
+    assert!(!is_inverse);
+    assert!(with_initial_reset);
+}
+
+#[test]
+fn phase_poly_box() {
+    let op_box = roundtrip_box(
+        json!({
+            "type": "PhasePolyBox",
+            "id": "5e4d3c2b-1a09-4f8e-b7d6-c5b4a3928170",
+            "n_qubits": 2,
+            "qubit_indices": [[["q", [0]], 0], [["q", [1]], 1]],
+            "phase_polynomial": [
+                [[true, false], "0.25"],
+                [[true, true], "a"]
+            ],
+            "linear_transformation": [[true, false], [true, true]]
+        }),
+        2,
+    );
+
+    let OpBox::PhasePolyBox {
+        n_qubits,
+        qubit_indices,
+        phase_polynomial,
+        linear_transformation,
+        ..
+    } = op_box
+    else {
+        panic!("Expected a PhasePolyBox");
+    };
+    assert_eq!(n_qubits, 2);
+    assert_eq!(qubit_indices[1].1, 1);
+    assert_eq!(
+        phase_polynomial,
+        vec![
+            (
+                Bitstring {
+                    vec: vec![true, false]
+                },
+                "0.25".to_string()
+            ),
+            (
+                Bitstring {
+                    vec: vec![true, true]
+                },
+                "a".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        linear_transformation,
+        vec![vec![true, false], vec![true, true]]
+    );
+}
+
+#[test]
+fn diagonal_box() {
+    let op_box = roundtrip_box(
+        json!({
+            "type": "DiagonalBox",
+            "id": "9a8b7c6d-5e4f-4a3b-8c2d-1e0f9a8b7c6d",
+            "diagonal": [
+                [[1.0, 0.0]],
+                [[0.0, 1.0]],
+                [[-1.0, 0.0]],
+                [[0.0, -1.0]]
+            ],
+            "upper_triangle": false
+        }),
+        2,
+    );
+
+    let OpBox::DiagonalBox {
+        diagonal,
+        upper_triangle,
+        ..
+    } = op_box
+    else {
+        panic!("Expected a DiagonalBox");
+    };
+    let entries: Vec<(f64, f64)> = diagonal.data.into_iter().flatten().collect();
+    assert_eq!(
+        entries,
+        vec![(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+    );
+    assert!(!upper_triangle);
+}
+
+#[test]
+fn multiplexed_u2_box() {
+    let op_box = roundtrip_box(
+        json!({
+            "type": "MultiplexedU2Box",
+            "id": "1c2d3e4f-5a6b-4c7d-8e9f-0a1b2c3d4e5f",
+            "op_map": [
+                [[false], {"type": "H"}],
+                [[true], {"type": "TK1", "params": ["0.5", "0", "1"]}]
+            ],
+            "impl_diag": true
+        }),
+        2,
+    );
+
+    let OpBox::MultiplexedU2Box {
+        op_map, impl_diag, ..
+    } = op_box
+    else {
+        panic!("Expected a MultiplexedU2Box");
+    };
+    assert_eq!(op_map.len(), 2);
+    assert_eq!(op_map[1].1.op_type, OpType::TK1);
+    assert!(impl_diag);
+}
+
+#[test]
+fn unitary_tableau_box() {
+    // The tableau of a CX followed by a Z on the control qubit.
+    let op_box = roundtrip_box(
+        json!({
+            "type": "UnitaryTableauBox",
+            "id": "7d6c5b4a-3928-4170-8e5d-4c3b2a190807",
+            "tab": {
+                "qubits": [["q", [0]], ["q", [1]]],
+                "tab": {
+                    "nrows": 4,
+                    "nqubits": 2,
+                    "xmat": [[true, true], [false, true], [false, false], [false, false]],
+                    "zmat": [[false, false], [false, false], [true, false], [true, true]],
+                    "phase": [[true], [false], [false], [false]]
+                }
+            }
+        }),
+        2,
+    );
+
+    let OpBox::UnitaryTableauBox { tab, .. } = op_box else {
+        panic!("Expected a UnitaryTableauBox");
+    };
+    assert_eq!(tab.xx(), vec![vec![true, true], vec![false, true]]);
+    assert_eq!(tab.xz(), vec![vec![false, false], vec![false, false]]);
+    assert_eq!(tab.zx(), vec![vec![false, false], vec![false, false]]);
+    assert_eq!(tab.zz(), vec![vec![true, false], vec![true, true]]);
+    assert_eq!(tab.x_phase(), vec![true, false]);
+    assert_eq!(tab.z_phase(), vec![false, false]);
+}
+
+#[test]
+fn unitary_box_dagger() {
+    let op_box = roundtrip_box(
+        json!({
+            "type": "Unitary1qBox",
+            "id": "2b3c4d5e-6f70-4182-93a4-b5c6d7e8f901",
+            "matrix": [
+                [[0.6, 0.0], [0.0, 0.8]],
+                [[0.0, 0.8], [0.6, 0.0]]
+            ]
+        }),
+        1,
+    );
+    let OpBox::Unitary1qBox { id, matrix } = &op_box else {
+        panic!("Expected a Unitary1qBox");
+    };
+    let dagger = op_box.dagger().unwrap();
+    let OpBox::Unitary1qBox {
+        id: dagger_id,
+        matrix: dagger_matrix,
+    } = &dagger
+    else {
+        panic!("Expected a Unitary1qBox");
+    };
+    assert_ne!(dagger_id, id);
+    assert_eq!(
+        dagger_matrix,
+        &[[(0.6, 0.0), (0.0, -0.8)], [(0.0, -0.8), (0.6, 0.0)]]
+    );
+    let OpBox::Unitary1qBox { matrix: twice, .. } = dagger.dagger().unwrap() else {
+        panic!("Expected a Unitary1qBox");
+    };
+    assert_eq!(&twice, matrix);
+
+    // The conjugate transpose of a two-qubit box swaps off-diagonal entries.
+    let mut entries = [[(0.0, 0.0); 4]; 4];
+    entries[0][1] = (0.0, 1.0);
+    entries[1][0] = (1.0, 0.0);
+    entries[2][2] = (1.0, 0.0);
+    entries[3][3] = (0.0, -1.0);
+    let op_box: OpBox = serde_json::from_value(json!({
+        "type": "Unitary2qBox",
+        "id": "3c4d5e6f-7081-4293-a4b5-c6d7e8f90a1b",
+        "matrix": entries.map(|row| row.map(|(re, im)| [re, im]))
+    }))
+    .unwrap();
+    let Some(OpBox::Unitary2qBox { matrix, .. }) = op_box.dagger() else {
+        panic!("Expected a Unitary2qBox");
+    };
+    assert_eq!(matrix[0][1], (1.0, 0.0));
+    assert_eq!(matrix[1][0], (0.0, -1.0));
+    assert_eq!(matrix[3][3], (0.0, 1.0));
+
+    assert_eq!(
+        OpBox::CircBox {
+            id: Default::default(),
+            circuit: SerialCircuit::new(None, "0".to_string())
+        }
+        .dagger(),
+        None
+    );
+}
+
+#[test]
+fn dummy_box_serializes_deterministically() {
+    let bounds = json!({"min": 1, "max": 2});
+    let dummy_box = json!({
+        "type": "DummyBox",
+        "id": "0a3b0e6c-5c1f-4a55-9d2e-3b7cb0f3f6a1",
+        "n_qubits": 2,
+        "n_bits": 0,
+        "resource_data": {
+            "op_type_count": {"Rz": bounds, "CX": bounds, "H": bounds, "T": bounds},
+            "gate_depth": bounds,
+            "op_type_depth": {"T": bounds, "CX": bounds, "Rz": bounds},
+            "two_qubit_gate_depth": bounds
+        }
+    });
+    roundtrip_box(dummy_box.clone(), 2);
+
+    // Each deserialization builds its maps anew, so unordered maps would
+    // likely be written in a different order.
+    let encodings: Vec<String> = (0..8)
+        .map(|_| {
+            let op_box: OpBox = serde_json::from_value(dummy_box.clone()).unwrap();
+            serde_json::to_string(&op_box).unwrap()
+        })
+        .collect();
+    assert!(encodings.windows(2).all(|pair| pair[0] == pair[1]));
+    // Entries are written in the declaration order of the operation types.
+    let counts = r#""op_type_count":{"T":{"min":1,"max":2},"H":{"min":1,"max":2},"Rz""#;
+    assert!(encodings[0].contains(counts), "{}", encodings[0]);
+}
+
+/// Box phases may be given as numbers, which are stored as strings.
+#[test]
+fn numeric_box_phases() {
+    let id = "9c2b7f1e-3d4a-4e5b-8f6c-7a8b9c0d1e2f";
+    let op_box: OpBox = serde_json::from_value(json!({
+        "type": "PauliExpBox",
+        "id": id,
+        "paulis": ["X", "Z"],
+        "phase": 0.5,
+        "cx_config": "Tree"
+    }))
+    .unwrap();
+    let OpBox::PauliExpBox { phase, .. } = &op_box else {
+        panic!("Expected a PauliExpBox, got {op_box:?}");
+    };
+    assert_eq!(phase, "0.5");
+    assert_eq!(serde_json::to_value(&op_box).unwrap()["phase"], "0.5");
+
+    let op_box: OpBox = serde_json::from_value(json!({
+        "type": "PauliExpPairBox",
+        "id": id,
+        "paulis_pair": [["X"], ["Z"]],
+        "phase_pair": [0.25, "a"],
+        "cx_config": "Tree"
+    }))
+    .unwrap();
+    let OpBox::PauliExpPairBox { phase_pair, .. } = &op_box else {
+        panic!("Expected a PauliExpPairBox, got {op_box:?}");
+    };
+    assert_eq!(phase_pair, &["0.25", "a"]);
+
+    let op_box: OpBox = serde_json::from_value(json!({
+        "type": "PauliExpCommutingSetBox",
+        "id": id,
+        "pauli_gadgets": [[["X"], 1], [["Z"], "b"]],
+        "cx_config": "Tree"
+    }))
+    .unwrap();
+    let OpBox::PauliExpCommutingSetBox { pauli_gadgets, .. } = &op_box else {
+        panic!("Expected a PauliExpCommutingSetBox, got {op_box:?}");
+    };
+    assert_eq!(pauli_gadgets[0].1, "1");
+    assert_eq!(pauli_gadgets[1].1, "b");
+
+    let infinite = serde_json::from_value::<OpBox>(json!({
+        "type": "PauliExpBox",
+        "id": id,
+        "paulis": ["X"],
+        "phase": "inf"
+    }));
+    assert!(infinite.is_err());
+}
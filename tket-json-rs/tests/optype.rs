@@ -0,0 +1,184 @@
+//! Tests for the operation type metadata.
+use std::str::FromStr;
+
+use rstest::rstest;
+use tket_json_rs::OpType;
+
+#[test]
+fn all_optypes() {
+    let all = OpType::all();
+    assert!(all.contains(&OpType::CX));
+    assert!(all.contains(&OpType::H));
+
+    for &op in all {
+        let json = serde_json::to_value(op).unwrap();
+        assert_eq!(json, op.to_string().as_str());
+        assert_eq!(serde_json::from_value::<OpType>(json).unwrap(), op);
+        assert_eq!(OpType::from_str(&op.to_string()), Ok(op));
+    }
+}
+
+#[rstest]
+#[case(OpType::H, true)]
+#[case(OpType::X, true)]
+#[case(OpType::Y, true)]
+#[case(OpType::Z, true)]
+#[case(OpType::CX, true)]
+#[case(OpType::CZ, true)]
+#[case(OpType::SWAP, true)]
+#[case(OpType::S, false)]
+#[case(OpType::T, false)]
+#[case(OpType::Rz, false)]
+#[case(OpType::ISWAPMax, false)]
+#[case(OpType::Measure, false)]
+fn self_inverse(#[case] op: OpType, #[case] expected: bool) {
+    assert_eq!(op.is_self_inverse(), expected);
+}
+
+#[rstest]
+#[case(OpType::H, Some(OpType::H))]
+#[case(OpType::S, Some(OpType::Sdg))]
+#[case(OpType::Tdg, Some(OpType::T))]
+#[case(OpType::CSX, Some(OpType::CSXdg))]
+#[case(OpType::Rz, None)]
+#[case(OpType::Measure, None)]
+fn dagger(#[case] op: OpType, #[case] expected: Option<OpType>) {
+    assert_eq!(op.dagger(), expected);
+    if let Some(dagger) = expected {
+        assert_eq!(dagger.dagger(), Some(op));
+    }
+}
+
+#[test]
+fn opcodes() {
+    let all = OpType::all();
+    for &op in all {
+        assert_eq!(OpType::from_opcode(op.opcode()), Some(op));
+    }
+
+    // Codes are assigned densely.
+    let mut codes: Vec<u16> = all.iter().map(OpType::opcode).collect();
+    codes.sort();
+    assert_eq!(codes, (0..all.len() as u16).collect::<Vec<_>>());
+    assert_eq!(OpType::from_opcode(all.len() as u16), None);
+
+    // Published codes must never change.
+    assert_eq!(OpType::Input.opcode(), 0);
+    assert_eq!(OpType::H.opcode(), 31);
+    assert_eq!(OpType::Rz.opcode(), 34);
+    assert_eq!(OpType::CX.opcode(), 40);
+}
+
+#[rstest]
+#[case(OpType::Rz, true)]
+#[case(OpType::TK2, true)]
+#[case(OpType::H, false)]
+#[case(OpType::CircBox, false)]
+fn parametrized(#[case] op: OpType, #[case] expected: bool) {
+    assert_eq!(op.is_parametrized(), expected);
+}
+
+#[rstest]
+#[case(OpType::CX, "cx")]
+#[case(OpType::Rz, "rz")]
+#[case(OpType::H, "h")]
+#[case(OpType::U1, "p")]
+#[case(OpType::Measure, "measure")]
+fn qiskit_names(#[case] op: OpType, #[case] name: &str) {
+    assert_eq!(op.to_qiskit_name(), Some(name));
+    assert_eq!(OpType::from_qiskit_name(name), Some(op));
+}
+
+#[test]
+fn qiskit_names_without_equivalent() {
+    assert_eq!(OpType::TK1.to_qiskit_name(), None);
+    assert_eq!(OpType::CircBox.to_qiskit_name(), None);
+    assert_eq!(OpType::from_qiskit_name("unitary"), None);
+    assert_eq!(OpType::from_qiskit_name("CX"), None);
+
+    // Legacy names are accepted, but never produced.
+    assert_eq!(OpType::from_qiskit_name("u3"), Some(OpType::U3));
+    assert_eq!(OpType::U3.to_qiskit_name(), Some("u"));
+}
+
+#[rstest]
+#[case(OpType::Rz, OpType::Rz, true)]
+#[case(OpType::Rz, OpType::CZ, true)]
+#[case(OpType::T, OpType::ZZPhase, true)]
+#[case(OpType::Rx, OpType::SX, true)]
+#[case(OpType::Ry, OpType::Y, true)]
+#[case(OpType::noop, OpType::H, true)]
+#[case(OpType::Rz, OpType::Rx, false)]
+#[case(OpType::H, OpType::H, false)]
+#[case(OpType::CX, OpType::Rz, false)]
+#[case(OpType::X, OpType::Ry, false)]
+fn commutes_trivially(#[case] a: OpType, #[case] b: OpType, #[case] expected: bool) {
+    assert_eq!(a.commutes_trivially_with(&b), expected);
+    assert_eq!(b.commutes_trivially_with(&a), expected);
+}
+
+#[rstest]
+#[case(OpType::Rz, &["0.5"], Some(&["-0.5"][..]))]
+#[case(OpType::Rz, &["a"], Some(&["-(a)"][..]))]
+#[case(OpType::TK1, &["0.1", "a", "0.3"], Some(&["-0.3", "-(a)", "-0.1"][..]))]
+#[case(OpType::U3, &["0.5", "0.25", "b"], Some(&["-0.5", "-(b)", "-0.25"][..]))]
+#[case(OpType::PhasedX, &["0.5", "0.25"], Some(&["-0.5", "0.25"][..]))]
+#[case(OpType::Rz, &["0.5", "0.5"], None)]
+#[case(OpType::H, &[], None)]
+fn inverse_params(#[case] op: OpType, #[case] params: &[&str], #[case] expected: Option<&[&str]>) {
+    let expected = expected.map(|p| p.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+    assert_eq!(op.inverse_params(params), expected);
+}
+
+#[rstest]
+#[case(OpType::CX, Some(1), true)]
+#[case(OpType::CRz, Some(1), true)]
+#[case(OpType::CCX, Some(2), true)]
+#[case(OpType::CnX, None, true)]
+#[case(OpType::QControlBox, None, true)]
+#[case(OpType::H, None, false)]
+#[case(OpType::ZZPhase, None, false)]
+fn controls(#[case] op: OpType, #[case] n_controls: Option<u32>, #[case] controlled: bool) {
+    assert_eq!(op.n_controls(), n_controls);
+    assert_eq!(op.is_controlled(), controlled);
+}
+
+#[rstest]
+#[case(OpType::H, &[], true)]
+#[case(OpType::CX, &[], true)]
+#[case(OpType::SWAP, &[], true)]
+#[case(OpType::T, &[], false)]
+#[case(OpType::Measure, &[], false)]
+#[case(OpType::Rz, &["0.5"], true)]
+#[case(OpType::Rz, &["-1.5"], true)]
+#[case(OpType::Rz, &["0.25"], false)]
+#[case(OpType::Rz, &["a"], false)]
+#[case(OpType::TK1, &["0.5", "1", "0"], true)]
+#[case(OpType::TK1, &["0.5", "0.1", "0"], false)]
+fn clifford(#[case] op: OpType, #[case] params: &[&str], #[case] expected: bool) {
+    assert_eq!(op.is_clifford(params), expected);
+}
+
+#[rstest]
+#[case(OpType::CX, Some("cx"))]
+#[case(OpType::SWAP, Some("swap"))]
+#[case(OpType::Sdg, Some("sdg"))]
+#[case(OpType::U3, Some("u3"))]
+#[case(OpType::ZZPhase, Some("rzz"))]
+#[case(OpType::Measure, Some("measure"))]
+#[case(OpType::TK1, None)]
+#[case(OpType::ISWAP, None)]
+#[case(OpType::CircBox, None)]
+fn qasm_names(#[case] op: OpType, #[case] expected: Option<&str>) {
+    assert_eq!(op.qasm_name(), expected);
+}
+
+#[rstest]
+#[case(OpType::Measure, true)]
+#[case(OpType::Reset, true)]
+#[case(OpType::Collapse, true)]
+#[case(OpType::Barrier, false)]
+#[case(OpType::H, false)]
+fn terminal_classical(#[case] op: OpType, #[case] expected: bool) {
+    assert_eq!(op.is_terminal_classical(), expected);
+}
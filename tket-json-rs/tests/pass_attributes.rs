@@ -0,0 +1,113 @@
+//! Tests for the attribute types used in pass definitions.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use rstest::rstest;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tket_json_rs::pass::standard::{
+    CxConfig, PauliSynthStrategy, RotationAxis, RoutingMethod, TargetTwoQubitGate,
+};
+
+/// Check that `Display` matches the serde name, and that `FromStr` inverts it.
+fn check_string_roundtrip<T>(value: T)
+where
+    T: Display + FromStr + Serialize + PartialEq + std::fmt::Debug,
+{
+    let name = value.to_string();
+    assert_eq!(serde_json::to_value(&value).unwrap(), name.as_str());
+    assert_eq!(T::from_str(&name).ok(), Some(value));
+}
+
+#[rstest]
+#[case(CxConfig::Snake)]
+#[case(CxConfig::Tree)]
+#[case(CxConfig::Star)]
+#[case(CxConfig::MultiQGate)]
+fn cx_config_strings(#[case] value: CxConfig) {
+    check_string_roundtrip(value);
+}
+
+#[rstest]
+#[case(PauliSynthStrategy::Individual)]
+#[case(PauliSynthStrategy::Pairwise)]
+#[case(PauliSynthStrategy::Sets)]
+#[case(PauliSynthStrategy::Greedy)]
+fn pauli_synth_strategy_strings(#[case] value: PauliSynthStrategy) {
+    check_string_roundtrip(value);
+}
+
+#[rstest]
+#[case(RotationAxis::Rx)]
+#[case(RotationAxis::Ry)]
+#[case(RotationAxis::Rz)]
+fn rotation_axis_strings(#[case] value: RotationAxis) {
+    check_string_roundtrip(value);
+}
+
+#[rstest]
+#[case(TargetTwoQubitGate::CX)]
+#[case(TargetTwoQubitGate::TK2)]
+fn target_two_qubit_gate_strings(#[case] value: TargetTwoQubitGate) {
+    check_string_roundtrip(value);
+}
+
+#[test]
+fn unknown_strings_are_rejected() {
+    assert!(CxConfig::from_str("Spiral").is_err());
+    assert!(CxConfig::from_str("snake").is_err());
+    assert!(PauliSynthStrategy::from_str("Random").is_err());
+    assert!(RotationAxis::from_str("Rw").is_err());
+    assert!(TargetTwoQubitGate::from_str("CZ").is_err());
+}
+
+#[rstest]
+#[case(json!({"name": "RoutingMethod"}), RoutingMethod::RoutingMethod)]
+#[case(
+    json!({"name": "LexiRouteRoutingMethod", "depth": 10}),
+    RoutingMethod::LexiRouteRoutingMethod { depth: 10 }
+)]
+#[case(json!({"name": "LexiLabellingMethod"}), RoutingMethod::LexiLabellingMethod)]
+#[case(
+    json!({"name": "AASRouteRoutingMethod", "aaswidth": 1, "cnotsynthtype": 2}),
+    RoutingMethod::AASRouteRoutingMethod { aaswidth: 1, cnotsynthtype: 2 }
+)]
+#[case(json!({"name": "AASLabellingMethod"}), RoutingMethod::AASLabellingMethod)]
+#[case(
+    json!({"name": "MultiGateReorderRoutingMethod", "depth": 10, "size": 10}),
+    RoutingMethod::MultiGateReorderRoutingMethod { depth: 10, size: 10 }
+)]
+#[case(
+    json!({"name": "BoxDecompositionRoutingMethod"}),
+    RoutingMethod::BoxDecompositionRoutingMethod
+)]
+#[case::unknown(
+    json!({"name": "CustomRoutingMethod", "depth": 3, "weights": [0.5, 1.0]}),
+    RoutingMethod::Other(
+        json!({"name": "CustomRoutingMethod", "depth": 3, "weights": [0.5, 1.0]})
+            .as_object()
+            .unwrap()
+            .clone()
+    )
+)]
+fn routing_method_roundtrip(#[case] initial_json: Value, #[case] expected: RoutingMethod) {
+    let method: RoutingMethod = serde_json::from_value(initial_json.clone()).unwrap();
+    assert_eq!(method, expected);
+    assert_eq!(serde_json::to_value(&method).unwrap(), initial_json);
+}
+
+/// Typed routing methods with missing or invalid fields are rejected rather
+/// than kept as [`RoutingMethod::Other`].
+#[rstest]
+#[case::missing_field(json!({"name": "LexiRouteRoutingMethod"}))]
+#[case::invalid_field(json!({"name": "LexiRouteRoutingMethod", "depth": "ten"}))]
+fn malformed_routing_method(#[case] initial_json: Value) {
+    assert!(serde_json::from_value::<RoutingMethod>(initial_json).is_err());
+}
+
+/// The name of the fallback variant is not a typed method.
+#[test]
+fn routing_method_named_other() {
+    let other: RoutingMethod = serde_json::from_value(json!({"name": "Other"})).unwrap();
+    assert!(matches!(other, RoutingMethod::Other(_)));
+}
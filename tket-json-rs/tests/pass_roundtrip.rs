@@ -1,13 +1,15 @@
 //! Pass roundtrip tests.
 use assert_json_diff::assert_json_eq;
-use serde_json::Value;
+use rstest::rstest;
+use serde_json::{json, Value};
 use tket_json_rs::pass::standard::{CliffordSimp, StandardPass, TargetTwoQubitGate};
-use tket_json_rs::pass::{BasePass, SequencePass};
+use tket_json_rs::pass::{BasePass, CompilationRecord, SequencePass};
 
 const STANDARD_CLIFFORD_SIMP: &str = include_str!("data/pass/standard_clifford_simp.json");
 const SEQUENCE: &str = include_str!("data/pass/sequence_clifford_remove.json");
 const REPEAT: &str = include_str!("data/pass/repeat_clifford.json");
 const REPEAT_UNTIL: &str = include_str!("data/pass/repeat_until_remove_no_mid_measure.json");
+const COMPILATION_RECORD: &str = include_str!("data/pass/compilation_record.json");
 
 #[test]
 fn standard_clifford_simp_roundtrip() {
@@ -74,3 +76,274 @@ fn repeat_until_remove_no_mid_measure_roundtrip() {
     let reencoded_json = serde_json::to_value(&pass).unwrap();
     assert_json_eq!(reencoded_json, initial_json);
 }
+
+#[test]
+fn compilation_record_roundtrip() {
+    let initial_json: Value = serde_json::from_str(COMPILATION_RECORD).unwrap();
+    let record: CompilationRecord = serde_json::from_value(initial_json.clone()).unwrap();
+
+    assert_eq!(record.circuit.commands.len(), 2);
+    assert_eq!(record.passes.len(), 2);
+    assert!(matches!(
+        &record.passes[0],
+        BasePass::StandardPass {
+            pass: StandardPass::CliffordSimp(_)
+        }
+    ));
+
+    let reencoded_json = serde_json::to_value(&record).unwrap();
+    assert_json_eq!(reencoded_json, initial_json);
+}
+
+#[test]
+fn compact_json() {
+    let pass: BasePass = serde_json::from_str(SEQUENCE).unwrap();
+
+    // Hand-written in the shape of pytket 1.x output, not a real export:
+    // those releases omit the target gate when it is the default.
+    let pytket_json = serde_json::json!({
+        "SequencePass": {
+            "sequence": [
+                {
+                    "StandardPass": {"allow_swaps": true, "name": "CliffordSimp"},
+                    "pass_class": "StandardPass"
+                },
+                {
+                    "StandardPass": {"name": "RemoveBarriers"},
+                    "pass_class": "StandardPass"
+                }
+            ]
+        },
+        "pass_class": "SequencePass"
+    });
+    let compact_json = pass.to_compact_json().unwrap();
+    assert_json_eq!(compact_json, pytket_json);
+
+    let roundtrip: BasePass = serde_json::from_value(compact_json).unwrap();
+    assert_eq!(pass, roundtrip);
+
+    // Non-default values are kept.
+    let tk2_json = serde_json::json!({
+        "StandardPass": {"allow_swaps": true, "name": "CliffordSimp", "target_2qb_gate": "TK2"},
+        "pass_class": "StandardPass"
+    });
+    let tk2: BasePass = serde_json::from_value(tk2_json.clone()).unwrap();
+    assert_json_eq!(tk2.to_compact_json().unwrap(), tk2_json);
+}
+
+/// Passes serialized by older pytket releases use different field spellings,
+/// and omit fields that were added later.
+#[test]
+fn legacy_field_spellings() {
+    let legacy_json = serde_json::json!({
+        "StandardPass": {
+            "allow_swaps": false,
+            "cx_fidelity": 0.99,
+            "name": "KAKDecomposition"
+        },
+        "pass_class": "StandardPass"
+    });
+    let pass: BasePass = serde_json::from_value(legacy_json).unwrap();
+
+    let BasePass::StandardPass {
+        pass: StandardPass::KAKDecomposition(kak),
+    } = &pass
+    else {
+        panic!("Expected a KAKDecomposition pass, got {pass:?}");
+    };
+    assert_eq!(kak.fidelity, 0.99);
+    assert!(!kak.allow_swaps);
+    assert_eq!(kak.target_2qb_gate, TargetTwoQubitGate::CX);
+
+    // The canonical spelling is used when serializing.
+    let reencoded_json = serde_json::to_value(&pass).unwrap();
+    assert_json_eq!(
+        reencoded_json,
+        serde_json::json!({
+            "StandardPass": {
+                "allow_swaps": false,
+                "fidelity": 0.99,
+                "name": "KAKDecomposition",
+                "target_2qb_gate": "CX"
+            },
+            "pass_class": "StandardPass"
+        })
+    );
+}
+
+/// Each alias is accepted in place of the canonical spelling, which is used
+/// when serializing.
+#[rstest]
+#[case::kak_fidelity(
+    json!({"name": "KAKDecomposition", "cx_fidelity": 0.99, "allow_swaps": true}),
+    json!({"name": "KAKDecomposition", "fidelity": 0.99, "allow_swaps": true, "target_2qb_gate": "CX"})
+)]
+#[case::euler_axes(
+    json!({"name": "EulerAngleReduction", "p": "Rz", "q": "Rx", "strict": true}),
+    json!({"name": "EulerAngleReduction", "euler_p": "Rz", "euler_q": "Rx", "euler_strict": true})
+)]
+#[case::pauli_strategy(
+    json!({"name": "PauliSimp", "strat": "Sets", "cx_config": "Snake"}),
+    json!({"name": "PauliSimp", "pauli_synth_strat": "Sets", "cx_config": "Snake"})
+)]
+#[case::swap_direction(
+    json!({"name": "DecomposeSwapsToCXs", "architecture": line_architecture(), "respect_direction": true}),
+    json!({"name": "DecomposeSwapsToCXs", "architecture": line_architecture(), "directed": true})
+)]
+#[case::mapping_direction(
+    json!({
+        "name": "CXMappingPass",
+        "architecture": line_architecture(),
+        "placement": {"type": "LinePlacement", "architecture": line_architecture()},
+        "routing_config": [{"name": "LexiRouteRoutingMethod", "depth": 10}],
+        "directed_cx": true,
+        "delay_measures": false
+    }),
+    json!({
+        "name": "CXMappingPass",
+        "architecture": line_architecture(),
+        "placement": {"type": "LinePlacement", "architecture": line_architecture()},
+        "routing_config": [{"name": "LexiRouteRoutingMethod", "depth": 10}],
+        "directed": true,
+        "delay_measures": false
+    })
+)]
+#[case::tk2_fidelities(
+    json!({
+        "name": "DecomposeTK2",
+        "fidelities": {"CX_fidelity": 0.99, "ZZMax_fidelity": 0.98, "ZZPhase_fidelity": 0.97},
+        "allow_swaps": true
+    }),
+    json!({
+        "name": "DecomposeTK2",
+        "fidelities": {"CX": 0.99, "ZZMax": 0.98, "ZZPhase": 0.97},
+        "allow_swaps": true
+    })
+)]
+fn pass_field_aliases(#[case] alias_json: Value, #[case] canonical_json: Value) {
+    let pass: StandardPass = serde_json::from_value(alias_json).unwrap();
+    assert_json_eq!(serde_json::to_value(&pass).unwrap(), canonical_json);
+
+    let canonical: StandardPass = serde_json::from_value(canonical_json).unwrap();
+    assert_eq!(pass, canonical);
+}
+
+/// A line of three nodes.
+fn line_architecture() -> Value {
+    json!({
+        "links": [
+            {"link": [["node", [0]], ["node", [1]]], "weight": 1},
+            {"link": [["node", [1]], ["node", [2]]], "weight": 1}
+        ],
+        "nodes": [["node", [0]], ["node", [1]], ["node", [2]]]
+    })
+}
+
+/// Integer options may be written as floats, and float options as integers.
+#[test]
+fn lenient_numeric_fields() {
+    let pass: StandardPass = serde_json::from_value(
+        serde_json::json!({"name": "ComposePhasePolyBoxes", "min_size": 2.0}),
+    )
+    .unwrap();
+    let StandardPass::ComposePhasePolyBoxes(compose) = &pass else {
+        panic!("Expected a ComposePhasePolyBoxes pass, got {pass:?}");
+    };
+    assert_eq!(compose.min_size, 2);
+    // Integers are written back in their canonical form.
+    assert_eq!(serde_json::to_value(&pass).unwrap()["min_size"], 2);
+
+    for min_size in [serde_json::json!(2.5), serde_json::json!(-1.0)] {
+        let result = serde_json::from_value::<StandardPass>(
+            serde_json::json!({"name": "ComposePhasePolyBoxes", "min_size": min_size}),
+        );
+        assert!(result.is_err());
+    }
+
+    let pass: StandardPass = serde_json::from_value(serde_json::json!({
+        "name": "GreedyPauliSimp",
+        "discount_rate": 0.7,
+        "depth_weight": 0.3,
+        "max_lookahead": 500,
+        "max_tqe_candidates": 500,
+        "seed": 5,
+        "allow_zzphase": false,
+        "thread_timeout": 100,
+        "only_reduce": false,
+        "trials": 1
+    }))
+    .unwrap();
+    let StandardPass::GreedyPauliSimp(greedy) = &pass else {
+        panic!("Expected a GreedyPauliSimp pass, got {pass:?}");
+    };
+    assert_eq!(greedy.seed, 5.0);
+    assert_eq!(greedy.max_lookahead, 500.0);
+}
+
+/// The exclusion lists of `DecomposeBoxes` may be omitted, but are always
+/// written back since the pytket schema requires them.
+#[test]
+fn decompose_boxes_empty_filters() {
+    let pytket_json = serde_json::json!({
+        "name": "DecomposeBoxes",
+        "excluded_types": [],
+        "excluded_opgroups": []
+    });
+    let pass: StandardPass = serde_json::from_value(pytket_json.clone()).unwrap();
+    assert_json_eq!(serde_json::to_value(&pass).unwrap(), pytket_json);
+
+    let sparse: StandardPass =
+        serde_json::from_value(serde_json::json!({"name": "DecomposeBoxes"})).unwrap();
+    assert_eq!(sparse, pass);
+}
+
+#[test]
+fn explain_error() {
+    let json = serde_json::json!({
+        "pass_class": "SequencePass",
+        "SequencePass": {"sequence": [
+            {"pass_class": "StandardPass", "StandardPass": {"name": "RemoveBarriers"}},
+            {
+                "pass_class": "RepeatPass",
+                "RepeatPass": {"body": {
+                    "pass_class": "StandardPass",
+                    "StandardPass": {"name": "CliffordSimp", "allow_swaps": "yes"}
+                }}
+            }
+        ]}
+    });
+    let report = tket_json_rs::pass::explain_error(&json.to_string());
+    assert!(
+        report.starts_with("$.SequencePass.sequence[1].RepeatPass.body.StandardPass.allow_swaps: "),
+        "{report}"
+    );
+    assert!(report.contains("expected a boolean"), "{report}");
+
+    let missing = serde_json::json!({
+        "pass_class": "StandardPass",
+        "StandardPass": {"name": "CliffordSimp"}
+    });
+    let report = tket_json_rs::pass::explain_error(&missing.to_string());
+    assert_eq!(report, "$.StandardPass: missing field `allow_swaps`");
+
+    assert!(tket_json_rs::pass::explain_error(SEQUENCE).is_empty());
+    assert!(tket_json_rs::pass::explain_error("{").starts_with("invalid JSON"));
+}
+
+/// The pytket encoding keeps the defaulted fields that pytket requires.
+#[test]
+fn pytket_dict_json() {
+    let pass = BasePass::StandardPass {
+        pass: StandardPass::CliffordSimp(CliffordSimp {
+            allow_swaps: true,
+            target_2qb_gate: TargetTwoQubitGate::CX,
+        }),
+    };
+    let pytket_json: Value = serde_json::from_str(STANDARD_CLIFFORD_SIMP).unwrap();
+    let json: Value = serde_json::from_str(&pass.to_pytket_dict_json()).unwrap();
+    assert_json_eq!(json, pytket_json);
+
+    let sequence: BasePass = serde_json::from_str(SEQUENCE).unwrap();
+    let json: Value = serde_json::from_str(&sequence.to_pytket_dict_json()).unwrap();
+    assert_json_eq!(json, serde_json::from_str::<Value>(SEQUENCE).unwrap());
+}
@@ -0,0 +1,561 @@
+//! Tests for the pass inspection utilities.
+use assert_json_diff::assert_json_eq;
+use std::collections::BTreeSet;
+
+use serde_json::json;
+use tket_json_rs::pass::architecture::MappingError;
+use tket_json_rs::pass::placement::PlacementType;
+use tket_json_rs::pass::registry::{self, RegistryError};
+use tket_json_rs::pass::standard::{
+    CXMappingPass, CliffordSimp, CostHint, HasAllowSwaps, PassCategory, StandardPass,
+    TargetTwoQubitGate,
+};
+use tket_json_rs::pass::{Architecture, BasePass, Placement, Predicate};
+use tket_json_rs::register::ElementId;
+use tket_json_rs::{OpType, SerialCircuit};
+
+/// A line architecture with three nodes.
+fn line_architecture() -> Architecture {
+    serde_json::from_value(json!({
+        "links": [
+            {"link": [["node", [0]], ["node", [1]]], "weight": 1},
+            {"link": [["node", [1]], ["node", [2]]], "weight": 1}
+        ],
+        "nodes": [["node", [0]], ["node", [1]], ["node", [2]]]
+    }))
+    .unwrap()
+}
+
+fn routing_pass() -> StandardPass {
+    serde_json::from_value(json!({
+        "name": "RoutingPass",
+        "architecture": line_architecture(),
+        "routing_config": [{"name": "LexiRouteRoutingMethod", "depth": 10}]
+    }))
+    .unwrap()
+}
+
+fn decompose_swaps_pass() -> StandardPass {
+    serde_json::from_value(json!({
+        "name": "DecomposeSwapsToCXs",
+        "architecture": line_architecture(),
+        "directed": false
+    }))
+    .unwrap()
+}
+
+#[test]
+fn routing_predicates() {
+    let routing = routing_pass();
+    assert_eq!(
+        routing.required_predicates(),
+        vec![Predicate::MaxTwoQubitGatesPredicate]
+    );
+    assert_eq!(
+        routing.guaranteed_predicates(),
+        vec![Predicate::ConnectivityPredicate {
+            architecture: line_architecture()
+        }]
+    );
+
+    // Decomposing swaps requires a routed circuit, so it must come after the
+    // routing pass in a pipeline.
+    let decompose = decompose_swaps_pass();
+    let required = decompose.required_predicates();
+    assert!(!required.is_empty());
+    assert!(required
+        .iter()
+        .all(|p| routing.guaranteed_predicates().contains(p)));
+
+    assert!(StandardPass::RemoveBarriers
+        .required_predicates()
+        .is_empty());
+    assert!(StandardPass::RemoveBarriers
+        .guaranteed_predicates()
+        .is_empty());
+}
+
+#[test]
+fn predicate_roundtrip() {
+    let initial_json = json!([
+        {"type": "GateSetPredicate", "allowed_types": ["CX", "Rz", "H"]},
+        {"type": "MaxNQubitsPredicate", "n_qubits": 3},
+        {"type": "PlacementPredicate", "node_set": [["node", [0]], ["node", [1]]]},
+        {"type": "NoMidMeasurePredicate"}
+    ]);
+    let predicates: Vec<Predicate> = serde_json::from_value(initial_json.clone()).unwrap();
+
+    assert_eq!(
+        predicates[0],
+        Predicate::GateSetPredicate {
+            allowed_types: vec![OpType::CX, OpType::Rz, OpType::H]
+        }
+    );
+    assert_eq!(
+        predicates[1],
+        Predicate::MaxNQubitsPredicate { n_qubits: 3 }
+    );
+
+    let reencoded_json = serde_json::to_value(&predicates).unwrap();
+    assert_json_eq!(reencoded_json, initial_json);
+}
+
+#[test]
+fn mapping_node_count_check() {
+    let circuit = |n_qubits: i64| -> SerialCircuit {
+        let qubits: Vec<_> = (0..n_qubits).map(|i| json!(["q", [i]])).collect();
+        serde_json::from_value(json!({
+            "bits": [],
+            "commands": [],
+            "implicit_permutation": [],
+            "phase": "0",
+            "qubits": qubits
+        }))
+        .unwrap()
+    };
+    let pass = CXMappingPass {
+        architecture: line_architecture(),
+        placement: Placement::new(PlacementType::GraphPlacement, line_architecture()),
+        routing_config: vec![],
+        directed: false,
+        delay_measures: true,
+    };
+
+    assert_eq!(pass.check_against(&circuit(2)), Ok(()));
+    assert_eq!(pass.check_against(&circuit(3)), Ok(()));
+    assert_eq!(
+        pass.check_against(&circuit(4)),
+        Err(MappingError::NotEnoughNodes {
+            n_nodes: 3,
+            n_qubits: 4
+        })
+    );
+
+    let StandardPass::RoutingPass(routing) = routing_pass() else {
+        unreachable!()
+    };
+    assert!(routing.check_against(&circuit(4)).is_err());
+}
+
+#[test]
+fn embedded_circuit_symbols() {
+    let pass: BasePass = serde_json::from_value(json!({
+        "pass_class": "SequencePass",
+        "SequencePass": {
+            "sequence": [
+                {
+                    "pass_class": "StandardPass",
+                    "StandardPass": {
+                        "name": "DecomposeSwapsToCircuit",
+                        "swap_replacement": {
+                            "bits": [],
+                            "commands": [
+                                {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+                                {"args": [["q", [1]], ["q", [0]]], "op": {"type": "CX"}},
+                                {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}}
+                            ],
+                            "implicit_permutation": [],
+                            "phase": "0.5*alpha + 1",
+                            "qubits": [["q", [0]], ["q", [1]]]
+                        }
+                    }
+                },
+                {"pass_class": "StandardPass", "StandardPass": {"name": "RemoveBarriers"}}
+            ]
+        }
+    }))
+    .unwrap();
+
+    let circuits = pass.circuits();
+    assert_eq!(circuits.len(), 1);
+    assert_eq!(circuits[0].phase, "0.5*alpha + 1");
+
+    let symbols: BTreeSet<String> = circuits.iter().flat_map(|c| c.free_symbols()).collect();
+    assert_eq!(symbols, BTreeSet::from(["alpha".to_string()]));
+}
+
+#[test]
+fn placement_roundtrip() {
+    let initial_json = json!({
+        "type": "GraphPlacement",
+        "architecture": line_architecture(),
+        "maximum_matches": 2000,
+        "timeout": 100
+    });
+    let placement: Placement = serde_json::from_value(initial_json.clone()).unwrap();
+
+    assert_eq!(placement.placement_type, PlacementType::GraphPlacement);
+    assert_eq!(placement.architecture, line_architecture());
+    assert_eq!(placement.parameters["timeout"], 100);
+
+    let reencoded_json = serde_json::to_value(&placement).unwrap();
+    assert_json_eq!(reencoded_json, initial_json);
+}
+
+#[test]
+fn allow_swaps() {
+    let mut clifford: StandardPass = serde_json::from_value(json!({
+        "name": "CliffordSimp",
+        "allow_swaps": true,
+        "target_2qb_gate": "CX"
+    }))
+    .unwrap();
+    assert_eq!(clifford.allow_swaps(), Some(true));
+    assert!(clifford.set_allow_swaps(false));
+    assert_eq!(clifford.allow_swaps(), Some(false));
+
+    let mut remove_barriers = StandardPass::RemoveBarriers;
+    assert_eq!(remove_barriers.allow_swaps(), None);
+    assert!(!remove_barriers.set_allow_swaps(false));
+    assert_eq!(routing_pass().allow_swaps(), None);
+}
+
+/// Calls `f` on every standard pass in `pass`.
+fn for_each_standard_pass(pass: &mut BasePass, f: &mut impl FnMut(&mut StandardPass)) {
+    match pass {
+        BasePass::StandardPass { pass } => f(pass),
+        BasePass::SequencePass { pass } => pass
+            .sequence
+            .iter_mut()
+            .for_each(|p| for_each_standard_pass(p, f)),
+        BasePass::RepeatPass { pass } => for_each_standard_pass(&mut pass.body, f),
+        BasePass::RepeatUntilSatisfiedPass { pass } => for_each_standard_pass(&mut pass.body, f),
+    }
+}
+
+#[test]
+fn retarget_pipeline() {
+    let mut pipeline: BasePass = serde_json::from_value(json!({
+        "pass_class": "SequencePass",
+        "SequencePass": {
+            "sequence": [
+                {
+                    "pass_class": "StandardPass",
+                    "StandardPass": {"name": "KAKDecomposition", "allow_swaps": true, "fidelity": 1.0}
+                },
+                {
+                    "pass_class": "RepeatPass",
+                    "RepeatPass": {"body": {
+                        "pass_class": "StandardPass",
+                        "StandardPass": {
+                            "name": "CliffordSimp",
+                            "allow_swaps": true,
+                            "target_2qb_gate": "CX"
+                        }
+                    }}
+                },
+                {"pass_class": "StandardPass", "StandardPass": {"name": "RemoveBarriers"}}
+            ]
+        }
+    }))
+    .unwrap();
+
+    let mut retargeted = 0;
+    for_each_standard_pass(&mut pipeline, &mut |pass| {
+        if pass.set_target_2qb_gate(TargetTwoQubitGate::TK2) {
+            retargeted += 1;
+        }
+    });
+    assert_eq!(retargeted, 2);
+
+    let mut targets = Vec::new();
+    for_each_standard_pass(&mut pipeline, &mut |pass| {
+        targets.push(pass.target_2qb_gate())
+    });
+    assert_eq!(
+        targets,
+        vec![
+            Some(TargetTwoQubitGate::TK2),
+            Some(TargetTwoQubitGate::TK2),
+            None
+        ]
+    );
+}
+
+#[test]
+fn registry_lookup() {
+    assert!(registry::is_known_pass("CliffordSimp"));
+    assert!(registry::known_pass_names().contains(&"RemoveBarriers"));
+
+    let pass = registry::deserialize_standard_pass(
+        "CliffordSimp",
+        &json!({"allow_swaps": false, "target_2qb_gate": "TK2"}),
+    )
+    .unwrap();
+    assert_eq!(
+        pass,
+        StandardPass::CliffordSimp(CliffordSimp {
+            allow_swaps: false,
+            target_2qb_gate: TargetTwoQubitGate::TK2
+        })
+    );
+    assert_eq!(
+        registry::deserialize_standard_pass("RemoveBarriers", &serde_json::Value::Null).unwrap(),
+        StandardPass::RemoveBarriers
+    );
+
+    assert!(matches!(
+        registry::deserialize_standard_pass("NoSuchPass", &json!({})),
+        Err(RegistryError::UnknownPass { .. })
+    ));
+    assert!(matches!(
+        registry::deserialize_standard_pass("CliffordSimp", &json!({})),
+        Err(RegistryError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn architecture_error_rates() {
+    let initial_json = json!({
+        "links": [{"link": [["node", [0]], ["node", [1]]], "weight": 1}],
+        "nodes": [["node", [0]], ["node", [1]]],
+        "node_errors": [[["node", [0]], 0.001], [["node", [1]], 0.002]],
+        "link_errors": [[[["node", [0]], ["node", [1]]], 0.01]],
+        "readout_errors": [[["node", [0]], 0.02], [["node", [1]], 0.03]]
+    });
+    let architecture: Architecture = serde_json::from_value(initial_json.clone()).unwrap();
+
+    let node = |i| ElementId("node".to_string(), vec![i]);
+    assert_eq!(
+        architecture.link_errors,
+        Some(vec![((node(0), node(1)), 0.01)])
+    );
+    assert_eq!(
+        architecture.readout_errors.as_ref().unwrap()[1],
+        (node(1), 0.03)
+    );
+
+    let reencoded_json = serde_json::to_value(&architecture).unwrap();
+    assert_json_eq!(reencoded_json, initial_json);
+
+    // Devices without characterisation data omit the fields.
+    let reencoded_line = serde_json::to_value(line_architecture()).unwrap();
+    assert!(reencoded_line.get("node_errors").is_none());
+}
+
+#[test]
+fn simplify_pipeline() {
+    let standard =
+        |name: &str| json!({"pass_class": "StandardPass", "StandardPass": {"name": name}});
+    let sequence = |passes: Vec<serde_json::Value>| json!({"pass_class": "SequencePass", "SequencePass": {"sequence": passes}});
+    let pass = |value: serde_json::Value| -> BasePass { serde_json::from_value(value).unwrap() };
+
+    // Adjacent duplicates of idempotent passes are removed.
+    let pipeline = pass(sequence(vec![
+        standard("RemoveBarriers"),
+        standard("RemoveBarriers"),
+        standard("SynthesiseTket"),
+        standard("SynthesiseTket"),
+        standard("RemoveBarriers"),
+    ]));
+    assert_eq!(
+        pipeline.simplify(),
+        pass(sequence(vec![
+            standard("RemoveBarriers"),
+            standard("SynthesiseTket"),
+            standard("SynthesiseTket"),
+            standard("RemoveBarriers"),
+        ]))
+    );
+
+    // Singleton sequences are unwrapped, including after duplicate removal.
+    let nested = pass(json!({
+        "pass_class": "RepeatPass",
+        "RepeatPass": {"body": sequence(vec![
+            sequence(vec![standard("RemoveRedundancies"), standard("RemoveRedundancies")])
+        ])}
+    }));
+    assert_eq!(
+        nested.simplify(),
+        pass(
+            json!({"pass_class": "RepeatPass", "RepeatPass": {"body": standard("RemoveRedundancies")}})
+        )
+    );
+}
+
+#[test]
+fn nested_pass_access() {
+    let mut pipeline: BasePass = serde_json::from_value(json!({
+        "pass_class": "SequencePass",
+        "SequencePass": {
+            "sequence": [
+                {"pass_class": "StandardPass", "StandardPass": {"name": "RemoveBarriers"}},
+                {
+                    "pass_class": "RepeatPass",
+                    "RepeatPass": {"body": {
+                        "pass_class": "StandardPass",
+                        "StandardPass": {
+                            "name": "CliffordSimp",
+                            "allow_swaps": true,
+                            "target_2qb_gate": "CX"
+                        }
+                    }}
+                }
+            ]
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(pipeline.get_at(&[]), Some(&pipeline));
+    assert!(matches!(
+        pipeline.get_at(&[0]),
+        Some(BasePass::StandardPass {
+            pass: StandardPass::RemoveBarriers
+        })
+    ));
+    assert!(pipeline.get_at(&[2]).is_none());
+    assert!(pipeline.get_at(&[1, 1]).is_none());
+    assert!(pipeline.get_at(&[0, 0]).is_none());
+
+    let Some(BasePass::StandardPass { pass }) = pipeline.get_at_mut(&[1, 0]) else {
+        panic!("Expected the body of the repeat pass");
+    };
+    assert!(pass.set_allow_swaps(false));
+    let Some(BasePass::StandardPass { pass }) = pipeline.get_at(&[1, 0]) else {
+        unreachable!()
+    };
+    assert_eq!(pass.allow_swaps(), Some(false));
+}
+
+#[test]
+fn pass_categories() {
+    let clifford_simp = StandardPass::CliffordSimp(CliffordSimp {
+        allow_swaps: true,
+        target_2qb_gate: TargetTwoQubitGate::CX,
+    });
+    assert_eq!(routing_pass().category(), PassCategory::Routing);
+    assert_eq!(clifford_simp.category(), PassCategory::Optimisation);
+    assert_eq!(
+        decompose_swaps_pass().category(),
+        PassCategory::Decomposition
+    );
+    assert_eq!(StandardPass::RebaseTket.category(), PassCategory::Rebase);
+    assert_eq!(
+        StandardPass::RemoveBarriers.category(),
+        PassCategory::Cleanup
+    );
+}
+
+#[test]
+fn pass_cost_hints() {
+    let greedy_pauli_simp: StandardPass = serde_json::from_value(json!({
+        "name": "GreedyPauliSimp",
+        "discount_rate": 0.7,
+        "depth_weight": 0.3,
+        "max_lookahead": 500,
+        "max_tqe_candidates": 500,
+        "seed": 0,
+        "allow_zzphase": false,
+        "thread_timeout": 100,
+        "only_reduce": false,
+        "trials": 1
+    }))
+    .unwrap();
+    assert_eq!(greedy_pauli_simp.cost_hint(), CostHint::Exponential);
+    assert_eq!(StandardPass::RemoveBarriers.cost_hint(), CostHint::Linear);
+    assert_eq!(routing_pass().cost_hint(), CostHint::Quadratic);
+    assert!(CostHint::Linear < CostHint::Exponential);
+}
+
+#[test]
+fn set_architecture() {
+    let mut pipeline = BasePass::SequencePass {
+        pass: serde_json::from_value(json!({"sequence": [
+            {"pass_class": "StandardPass", "StandardPass": routing_pass()},
+            {"pass_class": "StandardPass", "StandardPass": {"name": "RemoveBarriers"}},
+            {
+                "pass_class": "RepeatPass",
+                "RepeatPass": {"body": {"pass_class": "StandardPass", "StandardPass": decompose_swaps_pass()}}
+            }
+        ]}))
+        .unwrap(),
+    };
+    let ring: Architecture = serde_json::from_value(json!({
+        "links": [
+            {"link": [["node", [0]], ["node", [1]]], "weight": 1},
+            {"link": [["node", [1]], ["node", [2]]], "weight": 1},
+            {"link": [["node", [2]], ["node", [0]]], "weight": 1}
+        ],
+        "nodes": [["node", [0]], ["node", [1]], ["node", [2]]]
+    }))
+    .unwrap();
+
+    assert_eq!(pipeline.set_architecture(&ring), 2);
+    let mut predicates = Vec::new();
+    for_each_standard_pass(&mut pipeline, &mut |pass| {
+        predicates.extend(
+            pass.guaranteed_predicates()
+                .into_iter()
+                .chain(pass.required_predicates()),
+        )
+    });
+    assert_eq!(
+        predicates,
+        vec![
+            Predicate::ConnectivityPredicate {
+                architecture: ring.clone()
+            },
+            Predicate::MaxTwoQubitGatesPredicate,
+            Predicate::ConnectivityPredicate {
+                architecture: ring.clone()
+            },
+        ]
+    );
+}
+
+#[test]
+fn set_architecture_of_mapping_passes() {
+    let StandardPass::RoutingPass(routing) = routing_pass() else {
+        panic!("Expected a RoutingPass");
+    };
+    let placement = Placement::new(PlacementType::LinePlacement, line_architecture());
+    let full_mapping = StandardPass::FullMappingPass(routing.into_full_mapping(placement.clone()));
+    let cx_mapping: StandardPass = serde_json::from_value(json!({
+        "name": "CXMappingPass",
+        "architecture": line_architecture(),
+        "placement": placement,
+        "routing_config": [{"name": "LexiRouteRoutingMethod", "depth": 10}],
+        "directed": false,
+        "delay_measures": true
+    }))
+    .unwrap();
+    let ring: Architecture = serde_json::from_value(json!({
+        "links": [
+            {"link": [["node", [0]], ["node", [1]]], "weight": 1},
+            {"link": [["node", [1]], ["node", [2]]], "weight": 1},
+            {"link": [["node", [2]], ["node", [0]]], "weight": 1}
+        ],
+        "nodes": [["node", [0]], ["node", [1]], ["node", [2]]]
+    }))
+    .unwrap();
+
+    for mut pass in [full_mapping, cx_mapping] {
+        assert!(pass.set_architecture(&ring));
+        let (architecture, placement) = match &pass {
+            StandardPass::FullMappingPass(p) => (&p.architecture, &p.placement),
+            StandardPass::CXMappingPass(p) => (&p.architecture, &p.placement),
+            _ => unreachable!(),
+        };
+        assert_eq!(architecture, &ring);
+        assert_eq!(placement.architecture, ring);
+    }
+}
+
+#[test]
+fn routing_into_full_mapping() {
+    let StandardPass::RoutingPass(routing) = routing_pass() else {
+        panic!("Expected a RoutingPass");
+    };
+    let placement = Placement::new(PlacementType::LinePlacement, line_architecture());
+    let mapping = routing.clone().into_full_mapping(placement.clone());
+
+    assert_eq!(mapping.architecture, routing.architecture);
+    assert_eq!(mapping.routing_config, routing.routing_config);
+    assert_eq!(mapping.placement, placement);
+
+    let pass = StandardPass::FullMappingPass(mapping);
+    assert_eq!(
+        pass.guaranteed_predicates(),
+        routing_pass().guaranteed_predicates()
+    );
+}
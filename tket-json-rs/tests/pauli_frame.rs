@@ -0,0 +1,101 @@
+//! Tests for Pauli frame propagation.
+#![cfg(feature = "clifford")]
+
+use rstest::rstest;
+use serde_json::{json, Value};
+use tket_json_rs::circuit_json::pauli_frame::{Pauli, PauliString};
+use tket_json_rs::register::ElementId;
+use tket_json_rs::SerialCircuit;
+
+/// Builds a two-qubit circuit from a list of commands.
+fn circuit(commands: Value) -> SerialCircuit {
+    serde_json::from_value(json!({
+        "bits": [["c", [0]]],
+        "commands": commands,
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap()
+}
+
+/// Parses a signed Pauli string such as `-XZ`.
+fn pauli_string(s: &str) -> (bool, Vec<Pauli>) {
+    let paulis = s[1..]
+        .chars()
+        .map(|c| match c {
+            'I' => Pauli::I,
+            'X' => Pauli::X,
+            'Y' => Pauli::Y,
+            'Z' => Pauli::Z,
+            _ => panic!("Invalid Pauli {c}"),
+        })
+        .collect();
+    (s.starts_with('-'), paulis)
+}
+
+/// Returns the sign and operators of a Pauli string, for comparisons.
+fn parts(image: &PauliString) -> (bool, Vec<Pauli>) {
+    (image.negative, image.paulis.clone())
+}
+
+#[test]
+fn x_through_cx() {
+    let circuit = circuit(json!([
+        {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}}
+    ]));
+    let frame = circuit.pauli_frame().unwrap();
+    let q = |i| ElementId("q".to_string(), vec![i]);
+
+    let image = frame.x_image(&q(0)).unwrap();
+    assert_eq!(image.paulis, [Pauli::X, Pauli::X]);
+    assert!(!image.negative);
+    assert_eq!(frame.x_image(&q(1)).unwrap().paulis, [Pauli::I, Pauli::X]);
+    assert_eq!(frame.z_image(&q(0)).unwrap().paulis, [Pauli::Z, Pauli::I]);
+    assert_eq!(frame.z_image(&q(1)).unwrap().paulis, [Pauli::Z, Pauli::Z]);
+}
+
+/// The expected images of `X0`, `Z0`, `X1` and `Z1`.
+#[rstest]
+#[case::cy(json!({"type": "CY"}), ["+XY", "+ZI", "+ZX", "+ZZ"])]
+#[case::ecr(json!({"type": "ECR"}), ["-YX", "-ZI", "+IX", "+ZY"])]
+#[case::iswap_max(json!({"type": "ISWAPMax"}), ["+ZY", "+IZ", "+YZ", "+ZI"])]
+#[case::tk2(
+    json!({"type": "TK2", "params": ["0.5", "1", "1.5"]}),
+    ["+YZ", "+YX", "+ZY", "+XY"]
+)]
+fn two_qubit_gates(#[case] op: Value, #[case] expected: [&str; 4]) {
+    let circuit = circuit(json!([{"args": [["q", [0]], ["q", [1]]], "op": op}]));
+    let frame = circuit.pauli_frame().unwrap();
+    let images = [
+        &frame.x_images[0],
+        &frame.z_images[0],
+        &frame.x_images[1],
+        &frame.z_images[1],
+    ];
+    for (image, expected) in images.into_iter().zip(expected) {
+        assert_eq!(parts(image), pauli_string(expected), "expected {expected}");
+    }
+}
+
+#[test]
+fn single_qubit_gates() {
+    let circuit = circuit(json!([
+        {"args": [["q", [0]]], "op": {"type": "Ry", "params": ["0.5"]}},
+        {"args": [["q", [1]]], "op": {"type": "Y"}},
+        {"args": [["q", [0]], ["q", [1]]], "op": {"type": "Barrier"}}
+    ]));
+    let frame = circuit.pauli_frame().unwrap();
+    assert_eq!(parts(&frame.x_images[0]), pauli_string("-ZI"));
+    assert_eq!(parts(&frame.z_images[0]), pauli_string("+XI"));
+    assert_eq!(parts(&frame.x_images[1]), pauli_string("-IX"));
+    assert_eq!(parts(&frame.z_images[1]), pauli_string("-IZ"));
+}
+
+#[rstest]
+#[case::t_gate(json!({"args": [["q", [0]]], "op": {"type": "T"}}))]
+#[case::symbolic(json!({"args": [["q", [0]]], "op": {"type": "Rz", "params": ["a"]}}))]
+#[case::measure(json!({"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}}))]
+fn non_clifford_circuits(#[case] command: Value) {
+    assert_eq!(circuit(json!([command])).pauli_frame(), None);
+}
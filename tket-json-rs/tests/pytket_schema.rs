@@ -33,4 +33,17 @@ mod schema_tests {
         println!("{}", serde_json::to_string_pretty(&schema).unwrap());
         assert_json_include!(actual: schema, expected: tket_schema);
     }
+
+    /// The pass schema includes typed definitions for the nested device data.
+    #[test]
+    fn pass_schema_definitions() {
+        let schema = tket_json_rs::pass::export_schema();
+        let defs = schema.get("$defs").unwrap().as_object().unwrap();
+        for name in ["Architecture", "Placement", "Predicate"] {
+            assert!(defs.contains_key(name), "Missing definition for {name}");
+        }
+
+        let placement = serde_json::to_string(&defs["Placement"]).unwrap();
+        assert!(placement.contains("#/$defs/Architecture"));
+    }
 }
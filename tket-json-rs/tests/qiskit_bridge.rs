@@ -0,0 +1,137 @@
+//! Tests for the Qiskit dictionary bridge.
+use rstest::rstest;
+use serde_json::json;
+use tket_json_rs::qiskit::{QiskitCircuit, QiskitError, QiskitInstruction};
+use tket_json_rs::{OpType, SerialCircuit};
+
+fn bell_circuit() -> SerialCircuit {
+    serde_json::from_value(json!({
+        "bits": [["c", [0]], ["c", [1]]],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "H"}},
+            {"args": [["q", [0]], ["q", [1]]], "op": {"type": "CX"}},
+            {"args": [["q", [0]], ["c", [0]]], "op": {"type": "Measure"}},
+            {"args": [["q", [1]], ["c", [1]]], "op": {"type": "Measure"}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]], ["q", [1]]]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn bell_roundtrip() {
+    let circuit = bell_circuit();
+    let dict = circuit.to_qiskit_dict().unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&dict).unwrap(),
+        json!({
+            "num_qubits": 2,
+            "num_clbits": 2,
+            "global_phase": 0.0,
+            "instructions": [
+                {"name": "h", "qubits": [0], "clbits": [], "params": []},
+                {"name": "cx", "qubits": [0, 1], "clbits": [], "params": []},
+                {"name": "measure", "qubits": [0], "clbits": [0], "params": []},
+                {"name": "measure", "qubits": [1], "clbits": [1], "params": []}
+            ]
+        })
+    );
+
+    let decoded: QiskitCircuit =
+        serde_json::from_value(serde_json::to_value(&dict).unwrap()).unwrap();
+    assert_eq!(SerialCircuit::from_qiskit_dict(&decoded).unwrap(), circuit);
+}
+
+#[test]
+fn angles_are_converted() {
+    let dict: QiskitCircuit = serde_json::from_value(json!({
+        "num_qubits": 1,
+        "num_clbits": 0,
+        "instructions": [{"name": "rz", "qubits": [0], "params": [std::f64::consts::PI]}]
+    }))
+    .unwrap();
+    let circuit = SerialCircuit::from_qiskit_dict(&dict).unwrap();
+    assert_eq!(circuit.commands[0].op.op_type, OpType::Rz);
+    assert_eq!(circuit.commands[0].op.params(), ["1"]);
+}
+
+#[test]
+fn unsupported_operations() {
+    let mut circuit = bell_circuit();
+    circuit.commands[0].op.op_type = OpType::TK1;
+    assert_eq!(
+        circuit.to_qiskit_dict(),
+        Err(QiskitError::UnsupportedOperation {
+            index: 0,
+            op_type: OpType::TK1
+        })
+    );
+
+    let mut symbolic = bell_circuit();
+    symbolic.phase = "a".to_string();
+    assert_eq!(
+        symbolic.to_qiskit_dict(),
+        Err(QiskitError::SymbolicPhase {
+            phase: "a".to_string()
+        })
+    );
+
+    let dict: QiskitCircuit = serde_json::from_value(json!({
+        "num_qubits": 1,
+        "num_clbits": 0,
+        "instructions": [{"name": "unitary", "qubits": [0]}]
+    }))
+    .unwrap();
+    assert!(matches!(
+        SerialCircuit::from_qiskit_dict(&dict),
+        Err(QiskitError::UnknownGate { index: 0, .. })
+    ));
+}
+
+#[test]
+fn non_finite_angles() {
+    let dict = QiskitCircuit {
+        name: None,
+        num_qubits: 1,
+        num_clbits: 0,
+        global_phase: 0.0,
+        instructions: vec![QiskitInstruction {
+            name: "rz".to_string(),
+            qubits: vec![0],
+            clbits: vec![],
+            params: vec![f64::NAN],
+        }],
+    };
+    assert!(matches!(
+        SerialCircuit::from_qiskit_dict(&dict),
+        Err(QiskitError::NonFinite(_))
+    ));
+}
+
+#[rstest]
+#[case::infinity("inf", "0")]
+#[case::nan("NaN", "0")]
+#[case::overflow("1e308", "0")]
+#[case::infinite_phase("0.5", "-inf")]
+fn non_finite_params_to_qiskit(#[case] param: &str, #[case] phase: &str) {
+    // Deserialization rejects such literals, so they are set directly.
+    let mut circuit: SerialCircuit = serde_json::from_value(json!({
+        "bits": [],
+        "commands": [
+            {"args": [["q", [0]]], "op": {"type": "Rz", "params": ["0"]}}
+        ],
+        "implicit_permutation": [],
+        "phase": "0",
+        "qubits": [["q", [0]]]
+    }))
+    .unwrap();
+    circuit.phase = phase.to_string();
+    circuit.commands[0].op.params = Some(vec![param.to_string()]);
+    assert!(matches!(
+        circuit.to_qiskit_dict(),
+        Err(QiskitError::NonFinite(_))
+    ));
+}
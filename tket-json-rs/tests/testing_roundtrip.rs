@@ -0,0 +1,25 @@
+//! Property tests for the `proptest` strategies.
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use tket_json_rs::testing::{arb_circuit, assert_json_roundtrip, GateSpec};
+use tket_json_rs::OpType;
+
+proptest! {
+    #[test]
+    fn circuit_json_roundtrip(circ in arb_circuit(GateSpec::default_gate_set(), 4, 20)) {
+        assert_json_roundtrip(&circ);
+    }
+
+    #[test]
+    fn circuits_respect_gate_set(
+        circ in arb_circuit(vec![GateSpec::new(OpType::CX, 2, 0)], 3, 10)
+    ) {
+        prop_assert!(circ.qubits.len() >= 2);
+        for command in &circ.commands {
+            prop_assert_eq!(command.op.op_type, OpType::CX);
+            prop_assert_eq!(command.args.len(), 2);
+            prop_assert_ne!(&command.args[0], &command.args[1]);
+        }
+    }
+}